@@ -0,0 +1,68 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use sm::{collider::sweep_and_prune, CollisionMask, Fi32, PointFi32};
+
+/// Circles scattered over a stage-sized area with a fixed seed
+fn scatter(count: usize) -> Vec<(PointFi32, CollisionMask)> {
+    let mut seed = 0x2545_f491_u32;
+    let mut next = move |range: i32| {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+        (seed % (2 * range as u32)) as i32 - range
+    };
+    (0..count)
+        .map(|_| {
+            let position = PointFi32::new(next(1000), next(500));
+            let radius = Fi32::from_num(30 + next(10));
+            (
+                position,
+                CollisionMask::Circle(PointFi32::new(0, 0), radius),
+            )
+        })
+        .collect()
+}
+
+/// The Collider's original loop, checking every pair
+fn pairwise(bodies: &[(PointFi32, CollisionMask)]) -> Vec<(usize, usize)> {
+    let mut collisions = Vec::new();
+    for i in 0..bodies.len() {
+        for j in i + 1..bodies.len() {
+            if bodies[i].1.check(bodies[i].0, &bodies[j].1, bodies[j].0) {
+                collisions.push((i, j));
+            }
+        }
+    }
+    collisions
+}
+
+fn broadphase(bodies: &[(PointFi32, CollisionMask)]) -> Vec<(usize, usize)> {
+    let bounds = bodies
+        .iter()
+        .map(|(position, mask)| mask.bounds(*position))
+        .collect::<Vec<_>>();
+    sweep_and_prune(&bounds)
+        .into_iter()
+        .filter(|&(i, j)| bodies[i].1.check(bodies[i].0, &bodies[j].1, bodies[j].0))
+        .collect()
+}
+
+fn collider(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collider");
+    for count in [4, 64, 512, 2048] {
+        let bodies = scatter(count);
+        assert_eq!(pairwise(&bodies), broadphase(&bodies));
+        group.bench_with_input(BenchmarkId::new("pairwise", count), &bodies, |b, bodies| {
+            b.iter(|| pairwise(black_box(bodies)))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("sweep_and_prune", count),
+            &bodies,
+            |b, bodies| b.iter(|| broadphase(black_box(bodies))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, collider);
+criterion_main!(benches);
@@ -1,16 +1,18 @@
-use specs::{Join, System, WriteStorage};
+use specs::{Join, Read, System, WriteStorage};
 
-use crate::Sprite;
+use crate::{Sprite, TickRate};
 
 pub struct Animator;
 
 impl<'a> System<'a> for Animator {
-    type SystemData = WriteStorage<'a, Sprite>;
+    type SystemData = (WriteStorage<'a, Sprite>, Read<'a, TickRate>);
 
     fn run(&mut self, mut data: Self::SystemData) {
-        for sprite in (&mut data).join() {
+        for sprite in (&mut data.0).join() {
+            // Animations play at the same speed whatever the tick rate
+            let frame_ticks = data.1.ticks(sprite.animation_rate + 1).max(1);
             sprite.counter += 1;
-            if sprite.counter > sprite.animation_rate {
+            if sprite.counter >= frame_ticks {
                 sprite.current.set_x(
                     ((sprite.current.x as u32 + sprite.current.width()) % sprite.wrap) as i32,
                 );
@@ -0,0 +1,992 @@
+use sdl2::rect::Rect;
+use specs::Builder;
+
+use crate::{
+    AerialData, AttackData, AttackKind, ChargeData, CollisionData, CollisionLayer, CollisionMask,
+    Combo, Damage, Deflection, DeflectorData, Direction, DodgeData, Dodges, Fi32, FrameData, Grabs,
+    HitboxData, KnockbackData, MeterCost, MovementStats, Moveset, PhysicsData, PlayerState,
+    PlayerStatus, PointFi32, ProjectileData, Shield, Sprite, ThrowData, WallStats,
+};
+
+/// Playable characters. Each character's stats and frame data are defined here.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Character {
+    Samurai,
+    Fighter,
+    Shinobi,
+}
+
+// Indices into the textures array
+const ATTACK_1_TEXTURE: usize = 6;
+const ATTACK_2_TEXTURE: usize = 8;
+const ATTACK_3_TEXTURE: usize = 9;
+
+fn frames(startup: u32, active: u32, recovery: u32) -> FrameData {
+    FrameData {
+        startup,
+        active,
+        recovery,
+    }
+}
+
+/// Knockback is (base, growth). Launch is the direction when facing right.
+fn hitbox(
+    offset: (i32, i32),
+    radius: i32,
+    damage: i32,
+    knockback: (i32, i32),
+    launch: (i32, i32),
+) -> HitboxData {
+    HitboxData {
+        offset: PointFi32::new(offset.0, offset.1),
+        radius: Fi32::from_num(radius),
+        knockback: knockback_data(damage, knockback, launch),
+    }
+}
+
+fn throw(total_frames: u32, damage: i32, knockback: (i32, i32), launch: (i32, i32)) -> ThrowData {
+    ThrowData {
+        total_frames,
+        knockback: knockback_data(damage, knockback, launch),
+    }
+}
+
+fn knockback_data(damage: i32, knockback: (i32, i32), launch: (i32, i32)) -> KnockbackData {
+    KnockbackData {
+        damage: Fi32::from_num(damage),
+        base_knockback: Fi32::from_num(knockback.0),
+        knockback_growth: Fi32::from_num(knockback.1),
+        launch: PointFi32::new(launch.0, launch.1),
+    }
+}
+
+fn deflector(
+    active: (u32, u32),
+    offset: (i32, i32),
+    radius: i32,
+    deflection: Deflection,
+) -> DeflectorData {
+    DeflectorData {
+        active,
+        offset: PointFi32::new(offset.0, offset.1),
+        radius: Fi32::from_num(radius),
+        deflection,
+    }
+}
+
+fn attack(
+    animation: usize,
+    wrap: u32,
+    animation_rate: u32,
+    frames: FrameData,
+    hitboxes: Vec<HitboxData>,
+) -> AttackData {
+    AttackData {
+        animation,
+        wrap,
+        animation_rate,
+        frames,
+        hitboxes,
+        cancel_window: None,
+        aerial: None,
+        projectile: None,
+        deflector: None,
+        charge: None,
+        impulse: None,
+        helpless: false,
+        counter: None,
+        meter: None,
+    }
+}
+
+/// EX specials cost half a bar. Supers cost a full bar and freeze everyone else.
+fn meter(cost: i32, freeze: u32) -> Option<MeterCost> {
+    Some(MeterCost {
+        cost: Fi32::from_num(cost),
+        freeze,
+    })
+}
+
+/// Auto-cancel is (early, late)
+fn aerial(landing_lag: u32, auto_cancel: (u32, u32)) -> Option<AerialData> {
+    Some(AerialData {
+        landing_lag,
+        auto_cancel_early: auto_cancel.0,
+        auto_cancel_late: auto_cancel.1,
+    })
+}
+
+/// Smash attacks charge for up to a second, dealing up to 1.4x damage
+fn charge(frame: u32) -> Option<ChargeData> {
+    Some(ChargeData {
+        frame,
+        max_frames: 60,
+        max_multiplier: Fi32::lit("1.4"),
+    })
+}
+
+fn curve(speeds: &[f32]) -> Vec<Fi32> {
+    speeds.iter().map(|&speed| Fi32::from_num(speed)).collect()
+}
+
+impl Character {
+    /// Add the components shared by every character, standing idle at the given position
+    pub fn build<B: Builder>(self, builder: B, position: PointFi32, facing: Direction) -> B {
+        builder
+            .with(PhysicsData {
+                position,
+                speed: PointFi32::new(0, 0),
+                acceleration: PointFi32::new(0, 0),
+                wall_contact: None,
+                ground: None,
+            })
+            .with(Sprite {
+                spritesheet: 0,
+                current: Rect::new(0, 0, 128, 128),
+                wrap: 1024,
+                flip: facing == Direction::Left,
+                counter: 0,
+                animation_rate: 5,
+            })
+            .with(CollisionData {
+                mask: CollisionMask::Circle(PointFi32::new(0, 0), Fi32::from_num(36.0)),
+                layer: CollisionLayer::Pushbox,
+                repel_speed: Fi32::from_num(3.0),
+            })
+            .with(PlayerState {
+                status: PlayerStatus::Idle,
+                facing,
+                animation_counter: 0,
+                stun: 0,
+                attack_hits: Vec::new(),
+                dodge_direction: PointFi32::new(0, 0),
+                attack: AttackKind::Jab(0),
+                jab_timeout: 0,
+                charge: 0,
+                counter_damage: Fi32::ZERO,
+                knockback: PointFi32::new(0, 0),
+                grab: None,
+                mash: 0,
+                invincible: 0,
+            })
+            .with(Damage::default())
+            .with(Combo::default())
+            .with(self.movement_stats())
+            .with(self.shield())
+            .with(self.dodges())
+            .with(self.moveset())
+            .with(self.wall_stats())
+            .with(self.grabs())
+    }
+
+    pub const ALL: [Character; 3] = [Character::Samurai, Character::Fighter, Character::Shinobi];
+
+    pub fn movement_stats(self) -> MovementStats {
+        match self {
+            Character::Samurai => MovementStats {
+                max_speed: Fi32::from_num(1020),
+                acceleration: Fi32::from_num(9000),
+                friction: Fi32::from_num(4320),
+                gravity: Fi32::from_num(6480),
+                jump_power: Fi32::from_num(1320),
+                superjump_power: Fi32::from_num(1800),
+                air_acceleration: Fi32::from_num(3600),
+                air_max_speed: Fi32::from_num(600),
+                landing_lag: 3,
+                weight: Fi32::from_num(100),
+            },
+            Character::Fighter => MovementStats {
+                max_speed: Fi32::from_num(840),
+                acceleration: Fi32::from_num(7200),
+                friction: Fi32::from_num(5400),
+                gravity: Fi32::from_num(7200),
+                jump_power: Fi32::from_num(1260),
+                superjump_power: Fi32::from_num(1680),
+                air_acceleration: Fi32::from_num(2880),
+                air_max_speed: Fi32::from_num(540),
+                landing_lag: 4,
+                weight: Fi32::from_num(110),
+            },
+            Character::Shinobi => MovementStats {
+                max_speed: Fi32::from_num(1200),
+                acceleration: Fi32::from_num(10800),
+                friction: Fi32::from_num(3600),
+                gravity: Fi32::from_num(5760),
+                jump_power: Fi32::from_num(1380),
+                superjump_power: Fi32::from_num(1920),
+                air_acceleration: Fi32::from_num(4320),
+                air_max_speed: Fi32::from_num(660),
+                landing_lag: 2,
+                weight: Fi32::from_num(85),
+            },
+        }
+    }
+
+    pub fn shield(self) -> Shield {
+        let max_health = match self {
+            Character::Samurai => Fi32::from_num(50),
+            Character::Fighter => Fi32::from_num(55),
+            Character::Shinobi => Fi32::from_num(45),
+        };
+        Shield {
+            health: max_health,
+            max_health,
+            depletion: Fi32::from_num(0.15),
+            regeneration: Fi32::from_num(0.08),
+            break_frames: 240,
+            radius: Fi32::from_num(48),
+        }
+    }
+
+    pub fn dodges(self) -> Dodges {
+        match self {
+            Character::Samurai => Dodges {
+                spot_dodge: DodgeData {
+                    total_frames: 26,
+                    intangible: (3, 17),
+                    travel: Vec::new(),
+                },
+                roll: DodgeData {
+                    total_frames: 30,
+                    intangible: (4, 15),
+                    travel: curve(&[
+                        2.0, 6.0, 10.0, 12.0, 12.0, 12.0, 11.0, 10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0,
+                        3.0, 2.0, 1.0,
+                    ]),
+                },
+                air_dodge: DodgeData {
+                    total_frames: 34,
+                    intangible: (3, 29),
+                    travel: curve(&[
+                        14.0, 14.0, 13.0, 12.0, 11.0, 9.0, 7.0, 5.0, 3.0, 2.0, 1.0, 0.5,
+                    ]),
+                },
+                landing_lag: 11,
+            },
+            Character::Fighter => Dodges {
+                spot_dodge: DodgeData {
+                    total_frames: 28,
+                    intangible: (3, 16),
+                    travel: Vec::new(),
+                },
+                roll: DodgeData {
+                    total_frames: 33,
+                    intangible: (4, 14),
+                    travel: curve(&[
+                        2.0, 5.0, 8.0, 10.0, 10.0, 10.0, 9.0, 8.0, 7.0, 6.0, 5.0, 4.0, 3.0, 2.0,
+                        1.0,
+                    ]),
+                },
+                air_dodge: DodgeData {
+                    total_frames: 36,
+                    intangible: (3, 27),
+                    travel: curve(&[12.0, 12.0, 11.0, 10.0, 9.0, 7.0, 5.0, 3.0, 2.0, 1.0]),
+                },
+                landing_lag: 13,
+            },
+            Character::Shinobi => Dodges {
+                spot_dodge: DodgeData {
+                    total_frames: 24,
+                    intangible: (2, 17),
+                    travel: Vec::new(),
+                },
+                roll: DodgeData {
+                    total_frames: 28,
+                    intangible: (3, 15),
+                    travel: curve(&[
+                        4.0, 9.0, 13.0, 14.0, 14.0, 13.0, 12.0, 11.0, 9.0, 7.0, 5.0, 4.0, 3.0, 2.0,
+                        1.0,
+                    ]),
+                },
+                air_dodge: DodgeData {
+                    total_frames: 32,
+                    intangible: (2, 29),
+                    travel: curve(&[
+                        16.0, 16.0, 15.0, 13.0, 11.0, 9.0, 7.0, 5.0, 3.0, 2.0, 1.0, 0.5,
+                    ]),
+                },
+                landing_lag: 10,
+            },
+        }
+    }
+
+    pub fn moveset(self) -> Moveset {
+        match self {
+            Character::Samurai => Moveset {
+                jab: vec![
+                    AttackData {
+                        cancel_window: Some((7, 18)),
+                        ..attack(
+                            ATTACK_1_TEXTURE,
+                            768,
+                            2,
+                            frames(5, 4, 9),
+                            vec![hitbox((40, 0), 24, 3, (8, 20), (4, -1))],
+                        )
+                    },
+                    AttackData {
+                        cancel_window: Some((7, 16)),
+                        ..attack(
+                            ATTACK_2_TEXTURE,
+                            512,
+                            3,
+                            frames(5, 4, 7),
+                            vec![hitbox((44, -8), 26, 3, (8, 20), (4, -1))],
+                        )
+                    },
+                    attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        5,
+                        frames(8, 5, 11),
+                        vec![hitbox((48, 0), 30, 6, (30, 70), (2, -1))],
+                    ),
+                ],
+                jab_timeout: 8,
+                forward_tilt: attack(
+                    ATTACK_2_TEXTURE,
+                    512,
+                    3,
+                    frames(7, 3, 14),
+                    vec![hitbox((50, 0), 28, 8, (20, 80), (3, -1))],
+                ),
+                up_tilt: attack(
+                    ATTACK_3_TEXTURE,
+                    384,
+                    4,
+                    frames(6, 5, 13),
+                    vec![hitbox((10, -50), 32, 7, (25, 75), (0, -1))],
+                ),
+                down_tilt: attack(
+                    ATTACK_1_TEXTURE,
+                    768,
+                    2,
+                    frames(5, 3, 10),
+                    vec![hitbox((44, 24), 24, 6, (30, 50), (1, -2))],
+                ),
+                forward_smash: AttackData {
+                    charge: charge(12),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        7,
+                        frames(14, 4, 28),
+                        vec![hitbox((56, 0), 32, 15, (35, 100), (3, -1))],
+                    )
+                },
+                up_smash: AttackData {
+                    charge: charge(9),
+                    ..attack(
+                        ATTACK_2_TEXTURE,
+                        512,
+                        6,
+                        frames(11, 6, 26),
+                        vec![hitbox((0, -56), 36, 14, (35, 95), (0, -1))],
+                    )
+                },
+                down_smash: AttackData {
+                    charge: charge(6),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        4,
+                        frames(8, 4, 30),
+                        vec![
+                            hitbox((48, 20), 28, 13, (30, 90), (2, -1)),
+                            hitbox((-48, 20), 28, 13, (30, 90), (-2, -1)),
+                        ],
+                    )
+                },
+                neutral_aerial: AttackData {
+                    aerial: aerial(8, (3, 16)),
+                    ..attack(
+                        ATTACK_2_TEXTURE,
+                        512,
+                        3,
+                        frames(5, 8, 12),
+                        vec![hitbox((0, 0), 44, 7, (15, 80), (1, -1))],
+                    )
+                },
+                forward_aerial: AttackData {
+                    aerial: aerial(12, (3, 15)),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        3,
+                        frames(6, 7, 11),
+                        vec![hitbox((36, 8), 28, 8, (20, 90), (1, -1))],
+                    )
+                },
+                back_aerial: AttackData {
+                    aerial: aerial(14, (4, 18)),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        2,
+                        frames(7, 4, 14),
+                        vec![hitbox((-40, 0), 28, 11, (22, 95), (-2, -1))],
+                    )
+                },
+                up_aerial: AttackData {
+                    aerial: aerial(10, (3, 16)),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        4,
+                        frames(6, 5, 13),
+                        vec![hitbox((0, -48), 30, 9, (18, 85), (0, -1))],
+                    )
+                },
+                down_aerial: AttackData {
+                    aerial: aerial(18, (5, 30)),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        6,
+                        frames(14, 4, 20),
+                        vec![hitbox((0, 48), 28, 14, (25, 85), (0, 1))],
+                    )
+                },
+                neutral_special: AttackData {
+                    projectile: Some(ProjectileData {
+                        spawn_frame: 16,
+                        offset: PointFi32::new(40, 0),
+                        speed: PointFi32::new(9, 0),
+                        gravity: Fi32::ZERO,
+                        lifetime: 50,
+                        max_count: 1,
+                        radius: Fi32::from_num(18),
+                        knockback: knockback_data(8, (25, 50), (3, -1)),
+                        animation: None,
+                    }),
+                    ..attack(ATTACK_2_TEXTURE, 512, 5, frames(16, 1, 22), Vec::new())
+                },
+                side_special: AttackData {
+                    impulse: Some((10, PointFi32::new(16, 0))),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        3,
+                        frames(10, 6, 18),
+                        vec![hitbox((40, 0), 30, 10, (30, 70), (3, -1))],
+                    )
+                },
+                up_special: AttackData {
+                    impulse: Some((6, PointFi32::new(4, -26))),
+                    helpless: true,
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        5,
+                        frames(6, 10, 16),
+                        vec![hitbox((20, -30), 34, 9, (30, 60), (1, -3))],
+                    )
+                },
+                down_special: AttackData {
+                    deflector: Some(deflector((4, 15), (30, 0), 44, Deflection::Reflect)),
+                    ..attack(ATTACK_2_TEXTURE, 512, 4, frames(4, 12, 14), Vec::new())
+                },
+                counter_attack: None,
+                ex_special: Some(AttackData {
+                    projectile: Some(ProjectileData {
+                        spawn_frame: 12,
+                        offset: PointFi32::new(40, 0),
+                        speed: PointFi32::new(13, 0),
+                        gravity: Fi32::ZERO,
+                        lifetime: 50,
+                        max_count: 1,
+                        radius: Fi32::from_num(26),
+                        knockback: knockback_data(12, (40, 60), (3, -1)),
+                        animation: None,
+                    }),
+                    meter: meter(50, 0),
+                    ..attack(ATTACK_2_TEXTURE, 512, 4, frames(12, 1, 18), Vec::new())
+                }),
+                super_attack: Some(AttackData {
+                    impulse: Some((20, PointFi32::new(24, 0))),
+                    meter: meter(100, 40),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        6,
+                        frames(20, 8, 30),
+                        vec![hitbox((40, 0), 48, 24, (60, 110), (2, -1))],
+                    )
+                }),
+            },
+            Character::Fighter => Moveset {
+                jab: vec![
+                    AttackData {
+                        cancel_window: Some((6, 18)),
+                        ..attack(
+                            ATTACK_1_TEXTURE,
+                            768,
+                            2,
+                            frames(4, 3, 11),
+                            vec![hitbox((36, 0), 22, 2, (6, 20), (4, -1))],
+                        )
+                    },
+                    AttackData {
+                        cancel_window: Some((6, 12)),
+                        ..attack(
+                            ATTACK_2_TEXTURE,
+                            512,
+                            2,
+                            frames(4, 3, 5),
+                            vec![hitbox((38, 0), 22, 2, (6, 20), (4, -1))],
+                        )
+                    },
+                    attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        6,
+                        frames(9, 5, 14),
+                        vec![hitbox((44, -4), 30, 8, (35, 80), (2, -1))],
+                    ),
+                ],
+                jab_timeout: 6,
+                forward_tilt: attack(
+                    ATTACK_2_TEXTURE,
+                    512,
+                    4,
+                    frames(8, 3, 16),
+                    vec![hitbox((46, 0), 30, 10, (25, 85), (3, -1))],
+                ),
+                up_tilt: attack(
+                    ATTACK_3_TEXTURE,
+                    384,
+                    5,
+                    frames(7, 5, 15),
+                    vec![hitbox((8, -48), 34, 9, (30, 80), (0, -1))],
+                ),
+                down_tilt: attack(
+                    ATTACK_1_TEXTURE,
+                    768,
+                    3,
+                    frames(6, 3, 12),
+                    vec![hitbox((40, 24), 26, 8, (35, 55), (1, -2))],
+                ),
+                forward_smash: AttackData {
+                    charge: charge(14),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        8,
+                        frames(16, 4, 30),
+                        vec![hitbox((52, 0), 34, 18, (40, 105), (3, -1))],
+                    )
+                },
+                up_smash: AttackData {
+                    charge: charge(10),
+                    ..attack(
+                        ATTACK_2_TEXTURE,
+                        512,
+                        7,
+                        frames(12, 6, 28),
+                        vec![hitbox((0, -52), 38, 16, (40, 100), (0, -1))],
+                    )
+                },
+                down_smash: AttackData {
+                    charge: charge(7),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        5,
+                        frames(9, 4, 32),
+                        vec![
+                            hitbox((44, 20), 30, 15, (35, 95), (2, -1)),
+                            hitbox((-44, 20), 30, 15, (35, 95), (-2, -1)),
+                        ],
+                    )
+                },
+                neutral_aerial: AttackData {
+                    aerial: aerial(10, (3, 18)),
+                    ..attack(
+                        ATTACK_2_TEXTURE,
+                        512,
+                        3,
+                        frames(6, 8, 14),
+                        vec![hitbox((0, 0), 46, 9, (20, 85), (1, -1))],
+                    )
+                },
+                forward_aerial: AttackData {
+                    aerial: aerial(14, (2, 16)),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        3,
+                        frames(7, 6, 11),
+                        vec![hitbox((34, 10), 30, 10, (25, 95), (1, -1))],
+                    )
+                },
+                back_aerial: AttackData {
+                    aerial: aerial(16, (4, 20)),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        3,
+                        frames(8, 4, 16),
+                        vec![hitbox((-38, 0), 30, 13, (25, 100), (-2, -1))],
+                    )
+                },
+                up_aerial: AttackData {
+                    aerial: aerial(12, (3, 18)),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        5,
+                        frames(7, 5, 15),
+                        vec![hitbox((0, -46), 32, 11, (22, 90), (0, -1))],
+                    )
+                },
+                down_aerial: AttackData {
+                    aerial: aerial(20, (5, 32)),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        7,
+                        frames(16, 4, 22),
+                        vec![hitbox((0, 46), 30, 16, (30, 90), (0, 1))],
+                    )
+                },
+                neutral_special: attack(
+                    ATTACK_3_TEXTURE,
+                    384,
+                    6,
+                    frames(20, 4, 24),
+                    vec![hitbox((50, 0), 36, 16, (40, 90), (3, -1))],
+                ),
+                side_special: AttackData {
+                    impulse: Some((12, PointFi32::new(14, 0))),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        4,
+                        frames(12, 6, 20),
+                        vec![hitbox((40, 0), 32, 12, (35, 75), (3, -1))],
+                    )
+                },
+                up_special: AttackData {
+                    impulse: Some((7, PointFi32::new(3, -24))),
+                    helpless: true,
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        6,
+                        frames(7, 10, 18),
+                        vec![hitbox((16, -30), 36, 11, (35, 65), (1, -3))],
+                    )
+                },
+                down_special: AttackData {
+                    deflector: Some(deflector((5, 16), (30, 0), 44, Deflection::Absorb)),
+                    ..attack(ATTACK_2_TEXTURE, 512, 5, frames(5, 12, 16), Vec::new())
+                },
+                counter_attack: None,
+                ex_special: Some(AttackData {
+                    meter: meter(50, 0),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        5,
+                        frames(14, 5, 20),
+                        vec![hitbox((50, 0), 42, 20, (50, 100), (3, -1))],
+                    )
+                }),
+                super_attack: Some(AttackData {
+                    meter: meter(100, 40),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        7,
+                        frames(24, 6, 34),
+                        vec![
+                            hitbox((50, 0), 44, 28, (70, 110), (3, -2)),
+                            hitbox((0, -40), 56, 20, (60, 100), (0, -1)),
+                        ],
+                    )
+                }),
+            },
+            Character::Shinobi => Moveset {
+                jab: vec![
+                    AttackData {
+                        cancel_window: Some((5, 12)),
+                        ..attack(
+                            ATTACK_1_TEXTURE,
+                            768,
+                            1,
+                            frames(3, 3, 6),
+                            vec![hitbox((38, 0), 20, 2, (5, 15), (4, -1))],
+                        )
+                    },
+                    AttackData {
+                        cancel_window: Some((5, 12)),
+                        ..attack(
+                            ATTACK_2_TEXTURE,
+                            512,
+                            2,
+                            frames(3, 3, 6),
+                            vec![hitbox((40, -6), 20, 2, (5, 15), (4, -1))],
+                        )
+                    },
+                    attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        4,
+                        frames(6, 4, 10),
+                        vec![hitbox((46, 0), 26, 5, (25, 65), (3, -2))],
+                    ),
+                ],
+                jab_timeout: 10,
+                forward_tilt: attack(
+                    ATTACK_2_TEXTURE,
+                    512,
+                    2,
+                    frames(5, 3, 11),
+                    vec![hitbox((48, 0), 26, 6, (18, 75), (3, -1))],
+                ),
+                up_tilt: attack(
+                    ATTACK_3_TEXTURE,
+                    384,
+                    3,
+                    frames(4, 5, 10),
+                    vec![hitbox((10, -48), 30, 5, (22, 70), (0, -1))],
+                ),
+                down_tilt: attack(
+                    ATTACK_1_TEXTURE,
+                    768,
+                    1,
+                    frames(4, 3, 8),
+                    vec![hitbox((42, 24), 22, 4, (25, 45), (1, -2))],
+                ),
+                forward_smash: AttackData {
+                    charge: charge(9),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        6,
+                        frames(11, 4, 24),
+                        vec![hitbox((54, 0), 30, 13, (30, 95), (3, -1))],
+                    )
+                },
+                up_smash: AttackData {
+                    charge: charge(7),
+                    ..attack(
+                        ATTACK_2_TEXTURE,
+                        512,
+                        5,
+                        frames(9, 6, 22),
+                        vec![hitbox((0, -54), 34, 12, (30, 90), (0, -1))],
+                    )
+                },
+                down_smash: AttackData {
+                    charge: charge(5),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        3,
+                        frames(6, 4, 26),
+                        vec![
+                            hitbox((46, 20), 26, 11, (25, 85), (2, -1)),
+                            hitbox((-46, 20), 26, 11, (25, 85), (-2, -1)),
+                        ],
+                    )
+                },
+                neutral_aerial: AttackData {
+                    aerial: aerial(7, (3, 14)),
+                    ..attack(
+                        ATTACK_2_TEXTURE,
+                        512,
+                        2,
+                        frames(4, 8, 10),
+                        vec![hitbox((0, 0), 40, 5, (12, 75), (1, -1))],
+                    )
+                },
+                forward_aerial: AttackData {
+                    aerial: aerial(9, (4, 14)),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        2,
+                        frames(4, 6, 8),
+                        vec![hitbox((34, 6), 26, 6, (15, 85), (1, -1))],
+                    )
+                },
+                back_aerial: AttackData {
+                    aerial: aerial(11, (3, 16)),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        2,
+                        frames(5, 4, 11),
+                        vec![hitbox((-38, 0), 26, 9, (18, 90), (-2, -1))],
+                    )
+                },
+                up_aerial: AttackData {
+                    aerial: aerial(8, (3, 14)),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        3,
+                        frames(4, 5, 10),
+                        vec![hitbox((0, -46), 28, 7, (15, 80), (0, -1))],
+                    )
+                },
+                down_aerial: AttackData {
+                    aerial: aerial(15, (4, 26)),
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        5,
+                        frames(11, 4, 16),
+                        vec![hitbox((0, 46), 26, 11, (20, 80), (0, 1))],
+                    )
+                },
+                neutral_special: AttackData {
+                    projectile: Some(ProjectileData {
+                        spawn_frame: 6,
+                        offset: PointFi32::new(30, 0),
+                        speed: PointFi32::new(12, 6),
+                        gravity: Fi32::from_num(0.3),
+                        lifetime: 60,
+                        max_count: 2,
+                        radius: Fi32::from_num(10),
+                        knockback: knockback_data(4, (10, 30), (1, -1)),
+                        animation: None,
+                    }),
+                    ..attack(ATTACK_1_TEXTURE, 768, 2, frames(6, 1, 11), Vec::new())
+                },
+                side_special: AttackData {
+                    impulse: Some((8, PointFi32::new(20, 0))),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        2,
+                        frames(8, 6, 14),
+                        vec![hitbox((36, 0), 28, 8, (25, 65), (3, -1))],
+                    )
+                },
+                up_special: AttackData {
+                    impulse: Some((4, PointFi32::new(5, -30))),
+                    helpless: true,
+                    ..attack(
+                        ATTACK_3_TEXTURE,
+                        384,
+                        4,
+                        frames(4, 10, 12),
+                        vec![hitbox((16, -30), 30, 6, (25, 55), (1, -3))],
+                    )
+                },
+                down_special: AttackData {
+                    counter: Some(Fi32::lit("1.3")),
+                    ..attack(ATTACK_2_TEXTURE, 512, 5, frames(5, 20, 18), Vec::new())
+                },
+                counter_attack: Some(attack(
+                    ATTACK_3_TEXTURE,
+                    384,
+                    3,
+                    frames(4, 4, 14),
+                    vec![hitbox((40, 0), 36, 4, (40, 70), (3, -1))],
+                )),
+                ex_special: Some(AttackData {
+                    projectile: Some(ProjectileData {
+                        spawn_frame: 5,
+                        offset: PointFi32::new(30, 0),
+                        speed: PointFi32::new(16, 0),
+                        gravity: Fi32::ZERO,
+                        lifetime: 50,
+                        max_count: 1,
+                        radius: Fi32::from_num(14),
+                        knockback: knockback_data(9, (30, 50), (2, -1)),
+                        animation: None,
+                    }),
+                    meter: meter(50, 0),
+                    ..attack(ATTACK_1_TEXTURE, 768, 2, frames(5, 1, 10), Vec::new())
+                }),
+                super_attack: Some(AttackData {
+                    impulse: Some((16, PointFi32::new(30, 0))),
+                    meter: meter(100, 40),
+                    ..attack(
+                        ATTACK_1_TEXTURE,
+                        768,
+                        3,
+                        frames(16, 10, 26),
+                        vec![hitbox((30, 0), 40, 22, (55, 105), (3, -2))],
+                    )
+                }),
+            },
+        }
+    }
+
+    pub fn grabs(self) -> Grabs {
+        match self {
+            Character::Samurai => Grabs {
+                frames: frames(6, 3, 21),
+                offset: PointFi32::new(34, 0),
+                radius: Fi32::from_num(20),
+                hold_distance: Fi32::from_num(44),
+                pummel_damage: Fi32::from_num(1.5),
+                pummel_frames: 12,
+                max_hold_frames: 180,
+                forward_throw: throw(18, 8, (45, 60), (3, -2)),
+                back_throw: throw(22, 10, (55, 65), (-3, -2)),
+                up_throw: throw(20, 7, (60, 55), (0, -1)),
+                down_throw: throw(24, 6, (70, 40), (1, -4)),
+            },
+            Character::Fighter => Grabs {
+                frames: frames(7, 3, 24),
+                offset: PointFi32::new(38, 0),
+                radius: Fi32::from_num(22),
+                hold_distance: Fi32::from_num(46),
+                pummel_damage: Fi32::from_num(2),
+                pummel_frames: 16,
+                max_hold_frames: 200,
+                forward_throw: throw(20, 9, (50, 65), (3, -2)),
+                back_throw: throw(24, 12, (60, 70), (-3, -2)),
+                up_throw: throw(22, 8, (65, 60), (0, -1)),
+                down_throw: throw(26, 7, (75, 45), (1, -4)),
+            },
+            Character::Shinobi => Grabs {
+                frames: frames(5, 3, 18),
+                offset: PointFi32::new(30, 0),
+                radius: Fi32::from_num(18),
+                hold_distance: Fi32::from_num(40),
+                pummel_damage: Fi32::from_num(1),
+                pummel_frames: 8,
+                max_hold_frames: 160,
+                forward_throw: throw(16, 7, (40, 55), (3, -2)),
+                back_throw: throw(20, 9, (50, 60), (-3, -2)),
+                up_throw: throw(18, 6, (55, 50), (0, -1)),
+                down_throw: throw(20, 5, (65, 35), (1, -4)),
+            },
+        }
+    }
+
+    pub fn wall_stats(self) -> WallStats {
+        match self {
+            Character::Samurai => WallStats {
+                can_wall_jump: true,
+                wall_jump_power: Fi32::from_num(20),
+                wall_jump_speed: Fi32::from_num(9),
+                can_cling: false,
+                cling_slide_speed: Fi32::ZERO,
+                cling_frames: 0,
+            },
+            Character::Fighter => WallStats {
+                can_wall_jump: false,
+                wall_jump_power: Fi32::ZERO,
+                wall_jump_speed: Fi32::ZERO,
+                can_cling: false,
+                cling_slide_speed: Fi32::ZERO,
+                cling_frames: 0,
+            },
+            Character::Shinobi => WallStats {
+                can_wall_jump: true,
+                wall_jump_power: Fi32::from_num(23),
+                wall_jump_speed: Fi32::from_num(11),
+                can_cling: true,
+                cling_slide_speed: Fi32::from_num(1.5),
+                cling_frames: 90,
+            },
+        }
+    }
+}
@@ -1,56 +1,216 @@
-use specs::{Join, System, WriteStorage};
+use specs::shrev::{EventChannel, ReaderId};
+use specs::{
+    Entities, Entity, Join, LendJoin, Read, ReadStorage, System, SystemData, World, Write,
+    WriteStorage,
+};
 
-use crate::{CollisionData, Fi32, PhysicsData, PointFi32, Sprite};
+use crate::{
+    overflow, CollisionData, CollisionEvent, CollisionMask, CollisionMatrix, Fi32, Interaction,
+    MovementStats, PhysicsData, PointFi32, Team, TickRate,
+};
 
+/// Finds overlapping colliders whose layers interact and emits a `CollisionEvent` for each pair
 pub struct Collider;
 
 impl<'a> System<'a> for Collider {
     type SystemData = (
-        WriteStorage<'a, PhysicsData>,
-        WriteStorage<'a, CollisionData>,
-        WriteStorage<'a, Sprite>,
+        Entities<'a>,
+        ReadStorage<'a, PhysicsData>,
+        ReadStorage<'a, CollisionData>,
+        Write<'a, EventChannel<CollisionEvent>>,
+        Read<'a, CollisionMatrix>,
+        ReadStorage<'a, Team>,
     );
 
     fn run(&mut self, mut data: Self::SystemData) {
-        let mut collision_datas = (&mut data.1).join().collect::<Vec<_>>();
-        let mut physics_datas = (&mut data.0).join().collect::<Vec<_>>();
-        let sprites = (&mut data.2).join().collect::<Vec<_>>();
+        let matrix = &data.4;
+        let bodies = (&data.0, &data.1, &data.2, data.5.maybe())
+            .join()
+            .map(|(entity, physics_data, collision_data, team)| {
+                let body = (physics_data.position, physics_data.motion());
+                (entity, body, collision_data, team)
+            })
+            .collect::<Vec<_>>();
+        // Bounds cover the whole of this frame's motion so fast bodies can't skip past each other
+        let bounds = bodies
+            .iter()
+            .map(|(_, (position, motion), collision_data, _)| {
+                collision_data.mask.swept_bounds(*position, *motion)
+            })
+            .collect::<Vec<_>>();
+        let events = sweep_and_prune(&bounds)
+            .into_iter()
+            .filter_map(|(i, j)| {
+                let (a, a_body, a_data, a_team) = bodies[i];
+                let (b, b_body, b_data, b_team) = bodies[j];
+                let teammates = a_team.is_some() && a_team == b_team;
+                let interaction = matrix.get(a_data.layer, b_data.layer, teammates);
+                if interaction == Interaction::Ignore {
+                    return None;
+                }
+                overflow::track(a);
+                contact(a_body, &a_data.mask, b_body, &b_data.mask).map(
+                    |(time_of_impact, normal, depth)| CollisionEvent {
+                        a,
+                        b,
+                        interaction,
+                        time_of_impact,
+                        normal,
+                        depth,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+        data.3.iter_write(events);
+    }
+}
 
-        for cd in &mut collision_datas {
-            cd.status.0 = false;
-            cd.repel_vector = PointFi32::new(0, 0);
+/// The time of impact of two bodies given as (position, motion), along with the normal
+/// from a to b and their overlap depth at that time
+fn contact(
+    (a_position, a_motion): (PointFi32, PointFi32),
+    a_mask: &CollisionMask,
+    (b_position, b_motion): (PointFi32, PointFi32),
+    b_mask: &CollisionMask,
+) -> Option<(Fi32, PointFi32, Fi32)> {
+    let time_of_impact =
+        a_mask.time_of_impact(a_position, a_motion, b_mask, b_position, b_motion)?;
+    let a_position = a_position + a_motion * time_of_impact;
+    let b_position = b_position + b_motion * time_of_impact;
+    match (a_mask, b_mask) {
+        (CollisionMask::Circle(a_center, a_radius), CollisionMask::Circle(b_center, b_radius)) => {
+            let offset = (b_position + *b_center) - (a_position + *a_center);
+            Some((
+                time_of_impact,
+                offset.normalize(),
+                *a_radius + *b_radius - offset.dot(offset.normalize()),
+            ))
         }
-        for i in 0..collision_datas.len() {
-            for j in i + 1..collision_datas.len() {
-                let colliding = collision_datas[i].mask.check(
-                    physics_datas[i].position,
-                    &collision_datas[j].mask,
-                    physics_datas[j].position,
-                );
-                collision_datas[i].status.0 |= colliding;
-                collision_datas[j].status.0 |= colliding;
-                if colliding {
-                    collision_datas[i].repel_vector +=
-                        (physics_datas[i].position - physics_datas[j].position).normalize();
-                    collision_datas[j].repel_vector +=
-                        (physics_datas[j].position - physics_datas[i].position).normalize();
+        _ => None,
+    }
+}
+
+/// Stops pushing entities from moving further into each other, and moves overlapping
+/// ones apart at the faster of their `repel_speed`s. The lighter body moves further.
+/// Bodies at the same spot are split along x, the older entity going left.
+#[derive(Default)]
+pub struct Repel {
+    reader: Option<ReaderId<CollisionEvent>>,
+}
+
+impl<'a> System<'a> for Repel {
+    type SystemData = (
+        WriteStorage<'a, PhysicsData>,
+        Write<'a, EventChannel<CollisionEvent>>,
+        ReadStorage<'a, CollisionData>,
+        ReadStorage<'a, MovementStats>,
+        Read<'a, TickRate>,
+    );
+
+    fn setup(&mut self, world: &mut World) {
+        Self::SystemData::setup(world);
+        self.reader = Some(
+            world
+                .fetch_mut::<EventChannel<CollisionEvent>>()
+                .register_reader(),
+        );
+    }
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let Some(reader) = &mut self.reader else {
+            return;
+        };
+        let weight = |entity: Entity| {
+            data.3
+                .get(entity)
+                .map_or(Fi32::from_num(100), |stats| stats.weight)
+        };
+        let repel_speed = |entity: Entity| {
+            data.2
+                .get(entity)
+                .map_or(Fi32::ZERO, |collision_data| collision_data.repel_speed)
+        };
+
+        // Sum the directions away from everything each entity touches, and how far to move it
+        let mut repels: Vec<(Entity, PointFi32, PointFi32)> = Vec::new();
+        let pushes = data
+            .1
+            .read(reader)
+            .filter(|event| event.interaction == Interaction::Push);
+        for event in pushes {
+            overflow::track(event.a);
+            let push = event.depth.min(
+                data.4.per_frame(
+                    repel_speed(event.a)
+                        .max(repel_speed(event.b))
+                        .max(crate::MIN_REPEL_SPEED),
+                ),
+            );
+            let normal = if event.normal == PointFi32::default() {
+                let x = if event.a.id() < event.b.id() { 1 } else { -1 };
+                PointFi32::new(x, 0)
+            } else {
+                event.normal
+            };
+            let (a_weight, b_weight) = (weight(event.a), weight(event.b));
+            let total_weight = a_weight + b_weight;
+            let a_share = if total_weight > Fi32::ZERO {
+                push * b_weight / total_weight
+            } else {
+                push / 2
+            };
+            // b takes the remainder so rounding can't leave the pair stuck overlapping
+            let shares = (a_share, push - a_share);
+            for (entity, away, share) in [(event.a, -normal, shares.0), (event.b, normal, shares.1)]
+            {
+                match repels.iter_mut().find(|(other, ..)| *other == entity) {
+                    Some((_, repel_vector, correction)) => {
+                        *repel_vector += away;
+                        *correction += away * share;
+                    }
+                    None => repels.push((entity, away, away * share)),
                 }
             }
         }
-        for (i, sprite) in sprites.into_iter().enumerate() {
-            sprite.glow = collision_datas[i].status.0;
-
-            let speed_dot = physics_datas[i]
-                .speed
-                .dot(collision_datas[i].repel_vector)
-                .min(Fi32::ZERO);
-            physics_datas[i].speed -= collision_datas[i].repel_vector.normalize() * speed_dot;
-            let acceleration_dot = physics_datas[i]
-                .acceleration
-                .dot(collision_datas[i].repel_vector)
-                .min(Fi32::ZERO);
-            physics_datas[i].acceleration -=
-                collision_datas[i].repel_vector.normalize() * acceleration_dot;
+        for (entity, repel_vector, correction) in repels {
+            let Some(physics_data) = data.0.get_mut(entity) else {
+                continue;
+            };
+            overflow::track(entity);
+            if let Some(position) = physics_data.position.checked_add(correction) {
+                physics_data.position = position;
+            }
+            let speed_dot = physics_data.speed.dot(repel_vector).min(Fi32::ZERO);
+            physics_data.speed -= repel_vector.normalize() * speed_dot;
+            let acceleration_dot = physics_data.acceleration.dot(repel_vector).min(Fi32::ZERO);
+            physics_data.acceleration -= repel_vector.normalize() * acceleration_dot;
+        }
+    }
+}
+
+/// Sort-and-sweep broadphase over x, also checking y overlap.
+/// Returns the index pairs (i, j), i < j, whose bounds overlap, sorted so the narrowphase
+/// runs in the same order as a plain pairwise loop regardless of positions.
+pub fn sweep_and_prune(bounds: &[Option<(PointFi32, PointFi32)>]) -> Vec<(usize, usize)> {
+    let mut order = bounds
+        .iter()
+        .enumerate()
+        .filter_map(|(i, bound)| bound.map(|(min, max)| (i, min, max)))
+        .collect::<Vec<_>>();
+    // Ties are broken by index so the sweep itself is deterministic
+    order.sort_unstable_by_key(|&(i, min, _)| (min.x, i));
+
+    let mut pairs = Vec::new();
+    let mut active: Vec<(usize, PointFi32, PointFi32)> = Vec::new();
+    for (i, min, max) in order {
+        active.retain(|&(_, _, active_max)| active_max.x >= min.x);
+        for &(j, active_min, active_max) in &active {
+            if active_min.y <= max.y && min.y <= active_max.y {
+                pairs.push((i.min(j), i.max(j)));
+            }
         }
+        active.push((i, min, max));
     }
+    pairs.sort_unstable();
+    pairs
 }
@@ -0,0 +1,370 @@
+use specs::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::{
+    keyboard_input::held_direction, meter::gain_meter, overflow, AttackKind, CollisionData,
+    CollisionLayer, CollisionMatrix, Combo, Damage, Direction, Dodges, Fi32, Health, Hitlag,
+    InputBuffer, Interaction, KnockbackData, Meter, MovementStats, Moveset, PhysicsData, Player1,
+    PlayerState, PlayerStatus, PointFi32, Projectile, Ruleset, Shield, Team, TickRate,
+};
+
+pub struct Combat;
+
+struct Hit {
+    attacker: Entity,
+    victim: Entity,
+    knockback: KnockbackData,
+    direction: Direction,
+    /// Projectiles are destroyed on hit instead of entering hitlag
+    projectile: bool,
+}
+
+/// Add damage to a victim's percent and take it from their health.
+/// Returns the percent after the hit.
+pub fn take_damage(
+    percent: Option<&mut Damage>,
+    health: Option<&mut Health>,
+    damage: Fi32,
+) -> Fi32 {
+    if let Some(health) = health {
+        health.current -= damage;
+    }
+    match percent {
+        Some(percent) => {
+            percent.0 += damage;
+            percent.0
+        }
+        None => damage,
+    }
+}
+
+/// Smash-style knockback from the victim's percent after the hit
+pub fn knockback(percent: Fi32, knockback: &KnockbackData, weight: Fi32) -> Fi32 {
+    let scaled = (percent / 10 + percent * knockback.damage / 20) * 200
+        / (weight + Fi32::from_num(100))
+        * Fi32::lit("1.4")
+        + Fi32::from_num(18);
+    scaled * knockback.knockback_growth / 100 + knockback.base_knockback
+}
+
+/// Launch speed per frame and hitstun frames, with DI applied if a direction is held
+pub fn launch_speed(
+    knockback_data: &KnockbackData,
+    facing: Direction,
+    percent: Fi32,
+    weight: Fi32,
+    held: Option<PointFi32>,
+) -> (PointFi32, u32) {
+    let knockback = knockback(percent, knockback_data, weight);
+    let mut launch = knockback_data.launch(facing);
+    if let Some(held) = held {
+        launch = directional_influence(launch, held);
+    }
+    (
+        launch * (knockback * crate::KNOCKBACK_SPEED_MULTIPLIER),
+        (knockback * crate::HITSTUN_MULTIPLIER).to_num(),
+    )
+}
+
+/// Frames both sides of a hit are frozen for
+pub fn hitlag(damage: Fi32) -> u32 {
+    ((damage * crate::HITLAG_MULTIPLIER).to_num::<u32>() + crate::HITLAG_BASE)
+        .min(crate::HITLAG_MAX)
+}
+
+/// Blockstun frames from a hit on shield
+pub fn shieldstun(damage: Fi32) -> u32 {
+    (damage * crate::SHIELDSTUN_MULTIPLIER).to_num::<u32>() + crate::SHIELDSTUN_BASE
+}
+
+/// Rotate the launch towards the held direction by at most atan(DI_STRENGTH)
+pub fn directional_influence(launch: PointFi32, held: PointFi32) -> PointFi32 {
+    let perpendicular = held - launch * held.dot(launch);
+    (launch + perpendicular * crate::DI_STRENGTH).normalize()
+}
+
+impl<'a> System<'a> for Combat {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, PhysicsData>,
+        ReadStorage<'a, CollisionData>,
+        WriteStorage<'a, PlayerState>,
+        WriteStorage<'a, Shield>,
+        ReadStorage<'a, Dodges>,
+        ReadStorage<'a, Moveset>,
+        WriteStorage<'a, Damage>,
+        ReadStorage<'a, MovementStats>,
+        ReadStorage<'a, Player1>,
+        ReadExpect<'a, InputBuffer>,
+        WriteStorage<'a, Hitlag>,
+        WriteStorage<'a, Projectile>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, Combo>,
+        Read<'a, Ruleset>,
+        WriteStorage<'a, Meter>,
+        ReadStorage<'a, Team>,
+        Read<'a, CollisionMatrix>,
+        Read<'a, TickRate>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let tick_rate = *data.19;
+        // Whether a hit from one layer lands on another's body, going by their teams
+        let hits_body = |layer: CollisionLayer, attacker: Entity, victim: Entity| {
+            let team = data.17.get(attacker);
+            let teammates = team.is_some() && team == data.17.get(victim);
+            data.18.get(layer, CollisionLayer::Hurtbox, teammates) != Interaction::Ignore
+        };
+
+        for combo in (&mut data.14).join() {
+            combo.since_hit = combo.since_hit.saturating_add(1);
+        }
+
+        let mut hits = Vec::new();
+        for (attacker, attacker_physics, attacker_state, moveset) in
+            (&data.0, &data.1, &data.3, &data.6).join()
+        {
+            // Hitboxes are paused during hitlag
+            if attacker_state.status != PlayerStatus::Attacking || data.11.contains(attacker) {
+                continue;
+            }
+            let Some(attack) = moveset.get(attacker_state.attack) else {
+                continue;
+            };
+            overflow::track(attacker);
+            for (victim, victim_physics, victim_collision) in (&data.0, &data.1, &data.2).join() {
+                if victim == attacker
+                    || attacker_state.attack_hits.contains(&victim)
+                    || !hits_body(CollisionLayer::Hitbox, attacker, victim)
+                {
+                    continue;
+                }
+                if let Some(victim_state) = data.3.get(victim) {
+                    if victim_state.is_invincible()
+                        || data
+                            .5
+                            .get(victim)
+                            .is_some_and(|dodges| dodges.is_intangible(victim_state, &tick_rate))
+                    {
+                        continue;
+                    }
+                }
+                if !attack.frames.is_active(attacker_state.frame(&tick_rate)) {
+                    continue;
+                }
+                // Hitboxes are swept along this frame's motion so fast attackers can't skip past
+                let hitbox = attack.hitboxes.iter().find(|hitbox| {
+                    hitbox
+                        .mask(attacker_state.facing)
+                        .time_of_impact(
+                            attacker_physics.position,
+                            attacker_physics.motion(),
+                            &victim_collision.mask,
+                            victim_physics.position,
+                            victim_physics.motion(),
+                        )
+                        .is_some()
+                });
+                if let Some(hitbox) = hitbox {
+                    let damage = match attacker_state.attack {
+                        AttackKind::Counter => {
+                            attacker_state.counter_damage.max(hitbox.knockback.damage)
+                        }
+                        _ => {
+                            let multiplier = attack.charge.as_ref().map_or(Fi32::ONE, |charge| {
+                                charge.multiplier(tick_rate.frames(attacker_state.charge))
+                            });
+                            hitbox.knockback.damage * multiplier
+                        }
+                    };
+                    hits.push(Hit {
+                        attacker,
+                        victim,
+                        knockback: KnockbackData {
+                            damage,
+                            ..hitbox.knockback.clone()
+                        },
+                        direction: attacker_state.facing,
+                        projectile: false,
+                    });
+                }
+            }
+        }
+        for (projectile_entity, projectile_physics, projectile) in
+            (&data.0, &data.1, &data.12).join()
+        {
+            if projectile.is_destroyed() || data.11.contains(projectile_entity) {
+                continue;
+            }
+            overflow::track(projectile_entity);
+            let victim = (&data.0, &data.1, &data.2)
+                .join()
+                .find(|(victim, victim_physics, victim_collision)| {
+                    *victim != projectile.owner
+                        && hits_body(CollisionLayer::Projectile, projectile.owner, *victim)
+                        && !data.3.get(*victim).is_some_and(|victim_state| {
+                            victim_state.is_invincible()
+                                || data.5.get(*victim).is_some_and(|dodges| {
+                                    dodges.is_intangible(victim_state, &tick_rate)
+                                })
+                        })
+                        && projectile
+                            .mask()
+                            .time_of_impact(
+                                projectile_physics.position,
+                                projectile_physics.motion(),
+                                &victim_collision.mask,
+                                victim_physics.position,
+                                victim_physics.motion(),
+                            )
+                            .is_some()
+                })
+                .map(|(victim, ..)| victim);
+            if let Some(victim) = victim {
+                hits.push(Hit {
+                    attacker: projectile_entity,
+                    victim,
+                    knockback: projectile.data.knockback.clone(),
+                    direction: projectile.facing,
+                    projectile: true,
+                });
+            }
+        }
+
+        for hit in hits {
+            overflow::track(hit.victim);
+            // Projectile hits are credited to their owner
+            let attacker = match data.12.get(hit.attacker) {
+                Some(projectile) if hit.projectile => projectile.owner,
+                _ => hit.attacker,
+            };
+            if hit.projectile {
+                if let Some(projectile) = data.12.get_mut(hit.attacker) {
+                    projectile.lifetime = 0;
+                    data.0.delete(hit.attacker).unwrap();
+                }
+            } else if let Some(attacker_state) = data.3.get_mut(hit.attacker) {
+                attacker_state.attack_hits.push(hit.victim);
+            }
+            let counter = match (data.3.get(hit.victim), data.6.get(hit.victim)) {
+                (Some(victim_state), Some(moveset)) => {
+                    moveset.counter_multiplier(victim_state, &tick_rate)
+                }
+                _ => None,
+            };
+            let Some(victim_state) = data.3.get_mut(hit.victim) else {
+                continue;
+            };
+            let damage = hit.knockback.damage;
+            let hitlag = tick_rate.ticks(hitlag(damage));
+
+            // Counters negate the hit and retaliate
+            if let Some(multiplier) = counter {
+                if !hit.projectile {
+                    data.11
+                        .insert(hit.attacker, Hitlag::new(hitlag, false))
+                        .unwrap();
+                }
+                victim_state.status = PlayerStatus::Attacking;
+                victim_state.attack = AttackKind::Counter;
+                victim_state.attack_hits.clear();
+                victim_state.animation_counter = 0;
+                victim_state.counter_damage = damage * multiplier;
+                continue;
+            }
+            // Parries negate shieldstun, leaving the attacker frozen for longer
+            if victim_state.is_parrying(&tick_rate) {
+                if !hit.projectile {
+                    let freeze = hitlag + tick_rate.ticks(crate::PARRY_FREEZE);
+                    data.11
+                        .insert(hit.attacker, Hitlag::new(freeze, false))
+                        .unwrap();
+                }
+                continue;
+            }
+
+            if !hit.projectile {
+                data.11
+                    .insert(hit.attacker, Hitlag::new(hitlag, false))
+                    .unwrap();
+            }
+            data.11
+                .insert(hit.victim, Hitlag::new(hitlag, true))
+                .unwrap();
+            match (victim_state.is_shielding(), data.4.get_mut(hit.victim)) {
+                (true, Some(shield)) => {
+                    shield.health -= damage * crate::SHIELD_DAMAGE_MULTIPLIER;
+                    victim_state.status = PlayerStatus::Blockstun;
+                    victim_state.stun = tick_rate.ticks(shieldstun(damage));
+                    if let Some(victim_physics) = data.1.get_mut(hit.victim) {
+                        let pushback =
+                            tick_rate.per_frame(damage * crate::SHIELD_PUSHBACK_MULTIPLIER);
+                        victim_physics.speed.x = match hit.direction {
+                            Direction::Right => pushback,
+                            Direction::Left => -pushback,
+                        };
+                    }
+                }
+                _ => {
+                    let combo_hits = data
+                        .14
+                        .get(hit.victim)
+                        .filter(|combo| {
+                            victim_state.status == PlayerStatus::Hitstun
+                                && combo.attacker == Some(attacker)
+                        })
+                        .map_or(0, |combo| combo.hits);
+                    let damage = damage * data.15.combo.damage_multiplier(combo_hits);
+                    if let Some(combo) = data.14.get_mut(hit.victim) {
+                        if combo_hits == 0 {
+                            *combo = Combo {
+                                attacker: Some(attacker),
+                                ..Combo::default()
+                            };
+                        }
+                        combo.hits += 1;
+                        combo.damage += damage;
+                        combo.since_hit = 0;
+                    }
+
+                    gain_meter(&mut data.16, attacker, hit.victim, damage);
+
+                    let percent = take_damage(
+                        data.7.get_mut(hit.victim),
+                        data.13.get_mut(hit.victim),
+                        damage,
+                    );
+                    let weight = data
+                        .8
+                        .get(hit.victim)
+                        .map_or(Fi32::from_num(100), |stats| stats.weight);
+                    let held = data
+                        .9
+                        .contains(hit.victim)
+                        .then(|| held_direction(&data.10.most_recent()));
+                    let knockback = KnockbackData {
+                        damage,
+                        ..hit.knockback
+                    };
+                    let (launch, hitstun) =
+                        launch_speed(&knockback, hit.direction, percent, weight, held);
+                    let hitstun = (Fi32::from_num(hitstun)
+                        * data.15.combo.hitstun_multiplier(combo_hits))
+                    .to_num::<u32>()
+                    .max(1);
+                    let (launch, hitstun) = (
+                        tick_rate.per_frame_velocity(launch),
+                        tick_rate.ticks(hitstun),
+                    );
+                    if let Some(victim_physics) = data.1.get_mut(hit.victim) {
+                        victim_physics.speed = launch;
+                    }
+
+                    victim_state.status = PlayerStatus::Hitstun;
+                    victim_state.stun = hitstun;
+                    victim_state.knockback = launch;
+                }
+            }
+            victim_state.animation_counter = 0;
+        }
+    }
+}
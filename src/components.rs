@@ -1,6 +1,6 @@
 use crate::{Fi32, PointFi32};
 use sdl2::rect::Rect;
-use specs::prelude::{Component, VecStorage};
+use specs::prelude::{Component, Entity, VecStorage};
 use specs_derive::Component;
 use std::{
     collections::HashSet,
@@ -13,6 +13,15 @@ pub enum Direction {
     Left,
 }
 
+impl Direction {
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Right => Direction::Left,
+            Direction::Left => Direction::Right,
+        }
+    }
+}
+
 impl From<Direction> for bool {
     fn from(direction: Direction) -> bool {
         match direction {
@@ -31,14 +40,34 @@ pub enum PlayerStatus {
     Hitstun,
     Blockstun,
     Attacking,
+    ShieldBreak,
+    SpotDodge,
+    Roll,
+    AirDodge,
+    Helpless,
+    Landing,
+    WallCling,
+    Grabbing,
+    Holding,
+    Grabbed,
+    Throwing,
+    /// Knocked out. Counts down `stun` before respawning, unless out of stocks.
+    Dead,
+    /// Waiting on the respawn platform
+    Respawning,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Input {
     Move(Direction),
+    /// Points the stick up, for up attacks and throws, without jumping
+    Up,
     Jump,
     Crouch,
     Attack,
+    Shield,
+    Grab,
+    Special,
     Quit,
 }
 
@@ -60,14 +89,245 @@ impl Framerate {
     }
 }
 
-#[derive(Component, Debug)]
+/// How fast the simulation runs. Movement stats are defined per second, and all other speeds and
+/// frame data in frames at `BASE_TICK_RATE`. Both are converted to ticks where they are used,
+/// so a fighter moves and attacks the same way at any tick rate, up to `MAX_TICK_RATE`.
+#[derive(Debug, Clone, Copy)]
+pub struct TickRate {
+    /// Ticks per second of game time
+    pub hz: u32,
+    /// Physics steps each tick is split into, for smoother movement and collisions
+    pub substeps: u32,
+    /// Game time passing per second of real time, below one for slow motion
+    pub speed: Fi32,
+}
+
+impl Default for TickRate {
+    fn default() -> Self {
+        TickRate {
+            hz: crate::BASE_TICK_RATE,
+            substeps: 1,
+            speed: Fi32::ONE,
+        }
+    }
+}
+
+impl TickRate {
+    /// Real time between ticks
+    pub fn frame_time(&self) -> std::time::Duration {
+        let ticks_per_second = self.hz.max(1) as u64 * self.speed.to_bits().max(1) as u64;
+        std::time::Duration::from_nanos(
+            1_000_000_000 * Fi32::ONE.to_bits() as u64 / ticks_per_second,
+        )
+    }
+
+    /// A speed per second converted to per tick
+    pub fn per_tick(&self, per_second: Fi32) -> Fi32 {
+        scale_rounded(per_second, 1, self.hz.max(1) as i64)
+    }
+
+    /// An acceleration per second squared converted to per tick squared
+    pub fn per_tick_squared(&self, per_second_squared: Fi32) -> Fi32 {
+        scale_rounded(per_second_squared, 1, (self.hz.max(1) as i64).pow(2))
+    }
+
+    /// A speed per frame at `BASE_TICK_RATE` converted to per tick
+    pub fn per_frame(&self, per_frame: Fi32) -> Fi32 {
+        scale_rounded(
+            per_frame,
+            crate::BASE_TICK_RATE as i64,
+            self.hz.max(1) as i64,
+        )
+    }
+
+    /// An acceleration per frame squared at `BASE_TICK_RATE` converted to per tick squared
+    pub fn per_frame_squared(&self, per_frame_squared: Fi32) -> Fi32 {
+        let base = crate::BASE_TICK_RATE as i64;
+        scale_rounded(
+            per_frame_squared,
+            base * base,
+            (self.hz.max(1) as i64).pow(2),
+        )
+    }
+
+    /// A velocity per frame at `BASE_TICK_RATE` converted to per tick
+    pub fn per_frame_velocity(&self, per_frame: PointFi32) -> PointFi32 {
+        PointFi32 {
+            x: self.per_frame(per_frame.x),
+            y: self.per_frame(per_frame.y),
+        }
+    }
+
+    /// Ticks lasting as long as `frames` frames at `BASE_TICK_RATE`. Frame `frames` starts on
+    /// this tick.
+    pub fn ticks(&self, frames: u32) -> u32 {
+        let base = crate::BASE_TICK_RATE as u64;
+        ((frames as u64 * self.hz as u64 * 2 + base) / (base * 2)) as u32
+    }
+
+    /// The frame at `BASE_TICK_RATE` which tick `ticks` falls in, the last one whose `ticks`
+    /// has been reached
+    pub fn frames(&self, ticks: u32) -> u32 {
+        let base = crate::BASE_TICK_RATE as u64;
+        (((ticks as u64 * 2 + 1) * base - 1) / (self.hz.max(1) as u64 * 2)) as u32
+    }
+
+    /// Buffered inputs lasting as long as `frames` frames at `BASE_TICK_RATE`, capped so the
+    /// input before the window is still in the buffer
+    pub fn input_window(&self, frames: usize) -> usize {
+        (self.ticks(frames as u32) as usize).min(crate::COMMAND_BUFFER_SIZE - 1)
+    }
+}
+
+/// Multiply and divide to the nearest representable value, rather than truncating
+fn scale_rounded(value: Fi32, multiplier: i64, divisor: i64) -> Fi32 {
+    let bits = value.to_bits() as i64 * multiplier;
+    Fi32::from_bits(((bits * 2 + bits.signum() * divisor) / (divisor * 2)) as i32)
+}
+
+/// Optional game rules
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Ruleset {
+    /// Pressing shield shortly before landing during an aerial halves its landing lag
+    pub l_cancel: bool,
+    pub stocks: u32,
+    /// Starting health. Players are knocked out when it runs out.
+    pub stamina: Option<Fi32>,
+    pub combo: ComboRules,
+    /// Players build meter to spend on EX specials and supers
+    pub meter: bool,
+    /// Players are split between this many teams in spawn order. Zero for free for all.
+    pub teams: u32,
+}
+
+impl Ruleset {
+    /// Team of the `player`th player spawned, if playing in teams
+    pub fn team(&self, player: usize) -> Option<Team> {
+        (self.teams > 0).then(|| Team(player as u32 % self.teams))
+    }
+}
+
+/// Scaling applied to each hit after the first in a combo, so hitstun
+/// eventually runs out and combos can't go on forever
+#[derive(Debug, Clone, Copy)]
+pub struct ComboRules {
+    /// Damage multiplier compounded per hit
+    pub damage_scaling: Fi32,
+    pub min_damage_scaling: Fi32,
+    /// Hitstun multiplier compounded per hit
+    pub hitstun_decay: Fi32,
+}
+
+impl Default for ComboRules {
+    fn default() -> Self {
+        ComboRules {
+            damage_scaling: Fi32::lit("0.9"),
+            min_damage_scaling: Fi32::lit("0.3"),
+            hitstun_decay: Fi32::lit("0.85"),
+        }
+    }
+}
+
+impl ComboRules {
+    /// Damage multiplier for a hit after `hits` earlier hits in the combo
+    pub fn damage_multiplier(&self, hits: u32) -> Fi32 {
+        (0..hits)
+            .fold(Fi32::ONE, |multiplier, _| multiplier * self.damage_scaling)
+            .max(self.min_damage_scaling)
+    }
+
+    /// Hitstun multiplier for a hit after `hits` earlier hits in the combo
+    pub fn hitstun_multiplier(&self, hits: u32) -> Fi32 {
+        (0..hits).fold(Fi32::ONE, |multiplier, _| multiplier * self.hitstun_decay)
+    }
+}
+
+/// The match ends when at most one player has stocks left
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatchState {
+    pub over: bool,
+    pub winner: Option<Entity>,
+}
+
+/// Draw debug information such as intangibility over the game
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DebugOverlay(pub bool);
+
+#[derive(Component, Debug, Clone)]
 #[storage(VecStorage)]
 pub struct PhysicsData {
     pub position: PointFi32,
     pub speed: PointFi32,
     pub acceleration: PointFi32,
+    /// Side on which the entity is touching a wall
+    pub wall_contact: Option<Direction>,
+    /// Index of the stage platform the entity is standing on
+    pub ground: Option<usize>,
 }
 
+impl PhysicsData {
+    /// How far the next `Physics` step will move the entity
+    pub fn motion(&self) -> PointFi32 {
+        self.speed + self.acceleration
+    }
+}
+
+/// Accumulated damage percent, which increases knockback taken
+#[derive(Component, Debug, Clone, Copy, Default)]
+#[storage(VecStorage)]
+pub struct Damage(pub Fi32);
+
+/// Who last hit this player and when, along with the combo they are taking.
+/// Hits landed while the player is still in hitstun from the same attacker extend the combo.
+#[derive(Component, Debug, Clone, Copy, Default)]
+#[storage(VecStorage)]
+pub struct Combo {
+    pub attacker: Option<Entity>,
+    pub hits: u32,
+    pub damage: Fi32,
+    /// Ticks since the last hit
+    pub since_hit: u32,
+}
+
+/// Built by dealing and taking damage, and spent on EX specials and supers
+#[derive(Component, Debug, Clone, Copy)]
+#[storage(VecStorage)]
+pub struct Meter {
+    pub current: Fi32,
+    pub max: Fi32,
+}
+
+impl Meter {
+    pub fn new(max: Fi32) -> Self {
+        Meter {
+            current: Fi32::ZERO,
+            max,
+        }
+    }
+
+    pub fn gain(&mut self, amount: Fi32) {
+        self.current = (self.current + amount).min(self.max);
+    }
+
+    pub fn can_afford(&self, cost: &MeterCost) -> bool {
+        self.current >= cost.cost
+    }
+}
+
+/// Lives left, including the current one
+#[derive(Component, Debug, Clone, Copy)]
+#[storage(VecStorage)]
+pub struct Stocks(pub u32);
+
+/// Health for stamina matches, drained by damage
+#[derive(Component, Debug, Clone, Copy)]
+#[storage(VecStorage)]
+pub struct Health {
+    pub current: Fi32,
+    pub max: Fi32,
+}
+
+/// Speeds are per second and accelerations per second squared, see `per_tick`
 #[derive(Component, Debug)]
 #[storage(VecStorage)]
 pub struct MovementStats {
@@ -79,9 +339,30 @@ pub struct MovementStats {
     pub superjump_power: Fi32,
     pub air_acceleration: Fi32,
     pub air_max_speed: Fi32,
+    /// In frames at `BASE_TICK_RATE`, like other frame data
+    pub landing_lag: u32,
+    pub weight: Fi32,
 }
 
-#[derive(Component)]
+impl MovementStats {
+    /// The stats converted to distances per tick at the given tick rate
+    pub fn per_tick(&self, tick_rate: &TickRate) -> MovementStats {
+        MovementStats {
+            max_speed: tick_rate.per_tick(self.max_speed),
+            acceleration: tick_rate.per_tick_squared(self.acceleration),
+            friction: tick_rate.per_tick_squared(self.friction),
+            gravity: tick_rate.per_tick_squared(self.gravity),
+            jump_power: tick_rate.per_tick(self.jump_power),
+            superjump_power: tick_rate.per_tick(self.superjump_power),
+            air_acceleration: tick_rate.per_tick_squared(self.air_acceleration),
+            air_max_speed: tick_rate.per_tick(self.air_max_speed),
+            landing_lag: self.landing_lag,
+            weight: self.weight,
+        }
+    }
+}
+
+#[derive(Component, Clone)]
 #[storage(VecStorage)]
 pub struct Sprite {
     pub spritesheet: usize, // index into textures array
@@ -89,20 +370,608 @@ pub struct Sprite {
     pub wrap: u32,
     pub flip: bool,
     pub counter: u32,
+    /// Frames at `BASE_TICK_RATE` each animation frame is shown for, minus one
     pub animation_rate: u32,
-    pub glow: bool,
 }
 
 #[derive(Component)]
 #[storage(VecStorage)]
 pub struct Player1;
 
-#[derive(Component)]
+#[derive(Component, Clone)]
 #[storage(VecStorage)]
 pub struct PlayerState {
     pub status: PlayerStatus,
     pub facing: Direction,
+    /// Ticks since the current status or move started, see `frame`
     pub animation_counter: u32,
+    /// Ticks left of the current stun
+    pub stun: u32,
+    pub attack_hits: Vec<Entity>,
+    pub dodge_direction: PointFi32,
+    /// The current or most recent attack
+    pub attack: AttackKind,
+    /// Ticks left to continue the jab string before it resets
+    pub jab_timeout: u32,
+    /// Ticks the current smash attack has been charged for
+    pub charge: u32,
+    /// Damage of the pending counter attack
+    pub counter_damage: Fi32,
+    /// Ticks left of invincibility after respawning
+    pub invincible: u32,
+    /// Launch speed remaining from the last hit
+    pub knockback: PointFi32,
+    /// The entity being held, or holding this one
+    pub grab: Option<Entity>,
+    /// Inputs pressed while grabbed, counting towards escaping
+    pub mash: u32,
+}
+
+impl PlayerState {
+    /// Frame at `BASE_TICK_RATE` of the current status or move, for comparing with frame data
+    pub fn frame(&self, tick_rate: &TickRate) -> u32 {
+        tick_rate.frames(self.animation_counter)
+    }
+
+    /// Statuses which last until `stun` counts down to zero
+    pub fn is_stunned(&self) -> bool {
+        [
+            PlayerStatus::Hitstun,
+            PlayerStatus::Blockstun,
+            PlayerStatus::ShieldBreak,
+            PlayerStatus::Landing,
+            PlayerStatus::Throwing,
+        ]
+        .contains(&self.status)
+    }
+
+    pub fn is_dodging(&self) -> bool {
+        [
+            PlayerStatus::SpotDodge,
+            PlayerStatus::Roll,
+            PlayerStatus::AirDodge,
+        ]
+        .contains(&self.status)
+    }
+
+    pub fn is_airborne(&self) -> bool {
+        [
+            PlayerStatus::Jumping,
+            PlayerStatus::AirDodge,
+            PlayerStatus::Helpless,
+            PlayerStatus::WallCling,
+        ]
+        .contains(&self.status)
+            || (self.status == PlayerStatus::Attacking
+                && matches!(self.attack, AttackKind::Aerial(_)))
+    }
+
+    pub fn is_shielding(&self) -> bool {
+        [PlayerStatus::Blocking, PlayerStatus::Blockstun].contains(&self.status)
+    }
+
+    /// Cannot be hit or grabbed
+    pub fn is_invincible(&self) -> bool {
+        self.invincible > 0 || [PlayerStatus::Dead, PlayerStatus::Respawning].contains(&self.status)
+    }
+
+    /// Hits within the first PARRY_WINDOW frames of raising shield are parried
+    pub fn is_parrying(&self, tick_rate: &TickRate) -> bool {
+        self.status == PlayerStatus::Blocking && self.frame(tick_rate) < crate::PARRY_WINDOW
+    }
+
+    pub fn is_grabbing(&self) -> bool {
+        [
+            PlayerStatus::Grabbing,
+            PlayerStatus::Holding,
+            PlayerStatus::Grabbed,
+            PlayerStatus::Throwing,
+        ]
+        .contains(&self.status)
+    }
+}
+
+/// Entities in hitlag are frozen in place, apart from smash DI
+#[derive(Component, Clone)]
+#[storage(VecStorage)]
+pub struct Hitlag {
+    /// Ticks left frozen
+    pub frames: u32,
+    /// Victims can nudge their position with smash DI
+    pub sdi: bool,
+    /// State at the start of the frame, restored at the end of the frame
+    pub frozen: Option<Frozen>,
+}
+
+/// The simulated components of an entity in hitlag, as they were at the start of the frame
+#[derive(Clone)]
+pub struct Frozen {
+    pub physics_data: PhysicsData,
+    pub player_state: Option<PlayerState>,
+    pub sprite: Option<Sprite>,
+    pub shield: Option<Shield>,
+    pub combo: Option<Combo>,
+    pub projectile: Option<Projectile>,
+    pub meter: Option<Meter>,
+}
+
+impl Hitlag {
+    pub fn new(frames: u32, sdi: bool) -> Self {
+        Hitlag {
+            frames,
+            sdi,
+            frozen: None,
+        }
+    }
+}
+
+/// Shield health is drained while shielding and regenerates otherwise.
+/// Hits on shield also deal damage to the shield.
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Shield {
+    pub health: Fi32,
+    pub max_health: Fi32,
+    pub depletion: Fi32,
+    pub regeneration: Fi32,
+    pub break_frames: u32,
+    pub radius: Fi32,
+}
+
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct WallStats {
+    pub can_wall_jump: bool,
+    pub wall_jump_power: Fi32,
+    /// Horizontal speed away from the wall when wall jumping
+    pub wall_jump_speed: Fi32,
+    pub can_cling: bool,
+    pub cling_slide_speed: Fi32,
+    pub cling_frames: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct DodgeData {
+    pub total_frames: u32,
+    /// First and last intangible frames, inclusive
+    pub intangible: (u32, u32),
+    /// Speed along the dodge direction on each frame
+    pub travel: Vec<Fi32>,
+}
+
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Dodges {
+    pub spot_dodge: DodgeData,
+    pub roll: DodgeData,
+    pub air_dodge: DodgeData,
+    /// Landing lag after an air dodge or falling helplessly
+    pub landing_lag: u32,
+}
+
+impl Dodges {
+    pub fn get(&self, status: PlayerStatus) -> Option<&DodgeData> {
+        match status {
+            PlayerStatus::SpotDodge => Some(&self.spot_dodge),
+            PlayerStatus::Roll => Some(&self.roll),
+            PlayerStatus::AirDodge => Some(&self.air_dodge),
+            _ => None,
+        }
+    }
+
+    pub fn is_intangible(&self, player_state: &PlayerState, tick_rate: &TickRate) -> bool {
+        self.get(player_state.status).is_some_and(|dodge| {
+            (dodge.intangible.0..=dodge.intangible.1).contains(&player_state.frame(tick_rate))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AerialData {
+    pub landing_lag: u32,
+    /// Landing on or before this frame has no extra landing lag
+    pub auto_cancel_early: u32,
+    /// Landing on or after this frame has no extra landing lag
+    pub auto_cancel_late: u32,
+}
+
+impl AerialData {
+    pub fn auto_cancels(&self, frame: u32) -> bool {
+        frame <= self.auto_cancel_early || frame >= self.auto_cancel_late
+    }
+}
+
+/// Damage and launch dealt by a hit or a throw
+#[derive(Debug, Clone)]
+pub struct KnockbackData {
+    pub damage: Fi32,
+    pub base_knockback: Fi32,
+    pub knockback_growth: Fi32,
+    /// Launch direction when facing right
+    pub launch: PointFi32,
+}
+
+impl KnockbackData {
+    pub fn launch(&self, facing: Direction) -> PointFi32 {
+        mirror(self.launch, facing).normalize()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FramePhase {
+    Startup,
+    Active,
+    Recovery,
+}
+
+/// Timing of a move, counted from frame 0 where `animation_counter` starts. See
+/// `PlayerState::frame`.
+#[derive(Debug, Clone, Copy)]
+pub struct FrameData {
+    pub startup: u32,
+    pub active: u32,
+    pub recovery: u32,
+}
+
+impl FrameData {
+    pub fn total(&self) -> u32 {
+        self.startup + self.active + self.recovery
+    }
+
+    pub fn phase(&self, frame: u32) -> FramePhase {
+        if frame < self.startup {
+            FramePhase::Startup
+        } else if frame < self.startup + self.active {
+            FramePhase::Active
+        } else {
+            FramePhase::Recovery
+        }
+    }
+
+    pub fn is_active(&self, frame: u32) -> bool {
+        self.phase(frame) == FramePhase::Active
+    }
+
+    pub fn is_finished(&self, frame: u32) -> bool {
+        frame >= self.total()
+    }
+}
+
+/// Hitboxes are out during the active frames of their move
+#[derive(Debug, Clone)]
+pub struct HitboxData {
+    /// Offset from the attacker's position when facing right
+    pub offset: PointFi32,
+    pub radius: Fi32,
+    pub knockback: KnockbackData,
+}
+
+impl HitboxData {
+    pub fn mask(&self, facing: Direction) -> CollisionMask {
+        CollisionMask::Circle(mirror(self.offset, facing), self.radius)
+    }
+}
+
+/// Mirror a point defined facing right to the given facing
+pub fn mirror(point: PointFi32, facing: Direction) -> PointFi32 {
+    match facing {
+        Direction::Right => point,
+        Direction::Left => PointFi32 {
+            x: -point.x,
+            y: point.y,
+        },
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AttackData {
+    /// Index into textures array
+    pub animation: usize,
+    pub wrap: u32,
+    pub animation_rate: u32,
+    pub frames: FrameData,
+    pub hitboxes: Vec<HitboxData>,
+    /// Frames in which attacking again continues the string, inclusive
+    pub cancel_window: Option<(u32, u32)>,
+    pub aerial: Option<AerialData>,
+    pub projectile: Option<ProjectileData>,
+    pub deflector: Option<DeflectorData>,
+    pub charge: Option<ChargeData>,
+    /// Speed set on the given frame, when facing right
+    pub impulse: Option<(u32, PointFi32)>,
+    /// The user falls helplessly after the move
+    pub helpless: bool,
+    /// Hits during the active frames are negated and answered with the counter attack,
+    /// dealing the incoming damage times this, or its own damage if higher
+    pub counter: Option<Fi32>,
+    pub meter: Option<MeterCost>,
+}
+
+/// Meter spent when starting a move
+#[derive(Debug, Clone, Copy)]
+pub struct MeterCost {
+    pub cost: Fi32,
+    /// Frames everyone else is frozen for when the move starts
+    pub freeze: u32,
+}
+
+/// Smash attacks pause on `frame` while attack is held, growing stronger
+#[derive(Debug, Clone)]
+pub struct ChargeData {
+    pub frame: u32,
+    pub max_frames: u32,
+    /// Damage multiplier when fully charged
+    pub max_multiplier: Fi32,
+}
+
+impl ChargeData {
+    pub fn multiplier(&self, charge: u32) -> Fi32 {
+        Fi32::ONE
+            + (self.max_multiplier - Fi32::ONE) * charge.min(self.max_frames) as i32
+                / self.max_frames as i32
+    }
+}
+
+/// A projectile spawned by a move
+#[derive(Debug, Clone)]
+pub struct ProjectileData {
+    /// Frame of the move on which the projectile spawns
+    pub spawn_frame: u32,
+    /// Spawn offset from the owner's position when facing right
+    pub offset: PointFi32,
+    /// Initial speed when facing right
+    pub speed: PointFi32,
+    pub gravity: Fi32,
+    pub lifetime: u32,
+    /// Most projectiles from this move the owner can have at once
+    pub max_count: usize,
+    pub radius: Fi32,
+    pub knockback: KnockbackData,
+    /// Texture index, wrap and animation rate of the projectile's sprite, if it has one
+    pub animation: Option<(usize, u32, u32)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Deflection {
+    /// Send the projectile back, owned by the reflector
+    Reflect,
+    /// Destroy the projectile, healing its damage
+    Absorb,
+}
+
+/// An area of a move which reflects or absorbs projectiles
+#[derive(Debug, Clone)]
+pub struct DeflectorData {
+    /// First and last active frames, inclusive
+    pub active: (u32, u32),
+    /// Offset from the user's position when facing right
+    pub offset: PointFi32,
+    pub radius: Fi32,
+    pub deflection: Deflection,
+}
+
+impl DeflectorData {
+    pub fn mask(&self, facing: Direction) -> CollisionMask {
+        CollisionMask::Circle(mirror(self.offset, facing), self.radius)
+    }
+}
+
+/// An entity spawned by another entity's move
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Projectile {
+    pub owner: Entity,
+    /// The move which spawned it, for limiting the on-screen count
+    pub source: AttackKind,
+    pub facing: Direction,
+    /// Ticks left before despawning. Zero once destroyed.
+    pub lifetime: u32,
+    pub data: ProjectileData,
+}
+
+impl Projectile {
+    pub fn mask(&self) -> CollisionMask {
+        CollisionMask::Circle(PointFi32::new(0, 0), self.data.radius)
+    }
+
+    pub fn is_destroyed(&self) -> bool {
+        self.lifetime == 0
+    }
+}
+
+/// Stick direction relative to the player's facing
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Stick {
+    Neutral,
+    Forward,
+    Back,
+    Up,
+    Down,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum AttackKind {
+    /// Step in the jab string, which is the neutral tilt
+    Jab(usize),
+    Tilt(Stick),
+    Smash(Stick),
+    Aerial(Stick),
+    /// Side specials are `Special(Stick::Forward)`
+    Special(Stick),
+    /// Retaliation after a successful counter
+    Counter,
+    /// Meter-powered neutral special
+    Ex,
+    Super,
+}
+
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Moveset {
+    pub jab: Vec<AttackData>,
+    /// Frames after a jab ends in which attacking continues the string
+    pub jab_timeout: u32,
+    pub forward_tilt: AttackData,
+    pub up_tilt: AttackData,
+    pub down_tilt: AttackData,
+    pub forward_smash: AttackData,
+    pub up_smash: AttackData,
+    pub down_smash: AttackData,
+    pub neutral_aerial: AttackData,
+    pub forward_aerial: AttackData,
+    pub back_aerial: AttackData,
+    pub up_aerial: AttackData,
+    pub down_aerial: AttackData,
+    pub neutral_special: AttackData,
+    pub side_special: AttackData,
+    pub up_special: AttackData,
+    pub down_special: AttackData,
+    pub counter_attack: Option<AttackData>,
+    pub ex_special: Option<AttackData>,
+    pub super_attack: Option<AttackData>,
+}
+
+impl Moveset {
+    pub fn get(&self, attack: AttackKind) -> Option<&AttackData> {
+        match attack {
+            AttackKind::Jab(step) => self.jab.get(step),
+            AttackKind::Tilt(Stick::Forward) => Some(&self.forward_tilt),
+            AttackKind::Tilt(Stick::Up) => Some(&self.up_tilt),
+            AttackKind::Tilt(Stick::Down) => Some(&self.down_tilt),
+            AttackKind::Smash(Stick::Forward) => Some(&self.forward_smash),
+            AttackKind::Smash(Stick::Up) => Some(&self.up_smash),
+            AttackKind::Smash(Stick::Down) => Some(&self.down_smash),
+            AttackKind::Tilt(_) | AttackKind::Smash(_) => None,
+            AttackKind::Aerial(Stick::Neutral) => Some(&self.neutral_aerial),
+            AttackKind::Aerial(Stick::Forward) => Some(&self.forward_aerial),
+            AttackKind::Aerial(Stick::Back) => Some(&self.back_aerial),
+            AttackKind::Aerial(Stick::Up) => Some(&self.up_aerial),
+            AttackKind::Aerial(Stick::Down) => Some(&self.down_aerial),
+            AttackKind::Special(Stick::Neutral) => Some(&self.neutral_special),
+            AttackKind::Special(Stick::Forward | Stick::Back) => Some(&self.side_special),
+            AttackKind::Special(Stick::Up) => Some(&self.up_special),
+            AttackKind::Special(Stick::Down) => Some(&self.down_special),
+            AttackKind::Counter => self.counter_attack.as_ref(),
+            AttackKind::Ex => self.ex_special.as_ref(),
+            AttackKind::Super => self.super_attack.as_ref(),
+        }
+    }
+
+    /// Damage multiplier of the counter the player is in, if it is active
+    pub fn counter_multiplier(
+        &self,
+        player_state: &PlayerState,
+        tick_rate: &TickRate,
+    ) -> Option<Fi32> {
+        if player_state.status != PlayerStatus::Attacking {
+            return None;
+        }
+        self.get(player_state.attack)
+            .filter(|attack| attack.frames.is_active(player_state.frame(tick_rate)))
+            .and_then(|attack| attack.counter)
+    }
+
+    /// Every move along with its name
+    pub fn moves(&self) -> Vec<(String, &AttackData)> {
+        let mut moves = self
+            .jab
+            .iter()
+            .enumerate()
+            .map(|(step, attack)| (format!("jab_{}", step + 1), attack))
+            .collect::<Vec<_>>();
+        moves.extend(
+            [
+                ("forward_tilt", &self.forward_tilt),
+                ("up_tilt", &self.up_tilt),
+                ("down_tilt", &self.down_tilt),
+                ("forward_smash", &self.forward_smash),
+                ("up_smash", &self.up_smash),
+                ("down_smash", &self.down_smash),
+                ("neutral_aerial", &self.neutral_aerial),
+                ("forward_aerial", &self.forward_aerial),
+                ("back_aerial", &self.back_aerial),
+                ("up_aerial", &self.up_aerial),
+                ("down_aerial", &self.down_aerial),
+                ("neutral_special", &self.neutral_special),
+                ("side_special", &self.side_special),
+                ("up_special", &self.up_special),
+                ("down_special", &self.down_special),
+            ]
+            .map(|(name, attack)| (name.to_string(), attack)),
+        );
+        let optional = [
+            ("counter_attack", &self.counter_attack),
+            ("ex_special", &self.ex_special),
+            ("super_attack", &self.super_attack),
+        ];
+        for (name, attack) in optional {
+            if let Some(attack) = attack {
+                moves.push((name.to_string(), attack));
+            }
+        }
+        moves
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ThrowData {
+    /// Frames of recovery after releasing the victim
+    pub total_frames: u32,
+    pub knockback: KnockbackData,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ThrowKind {
+    Forward,
+    Back,
+    Up,
+    Down,
+}
+
+/// Grabs ignore shields. A held victim can be pummelled or thrown, and escapes by mashing.
+#[derive(Component, Debug, Clone)]
+#[storage(VecStorage)]
+pub struct Grabs {
+    pub frames: FrameData,
+    /// Offset of the grab box from the grabber's position when facing right
+    pub offset: PointFi32,
+    pub radius: Fi32,
+    /// Distance in front of the grabber at which the victim is held
+    pub hold_distance: Fi32,
+    pub pummel_damage: Fi32,
+    /// Minimum frames between pummels
+    pub pummel_frames: u32,
+    /// The victim is released after being held this long
+    pub max_hold_frames: u32,
+    pub forward_throw: ThrowData,
+    pub back_throw: ThrowData,
+    pub up_throw: ThrowData,
+    pub down_throw: ThrowData,
+}
+
+impl Grabs {
+    pub fn mask(&self, facing: Direction) -> CollisionMask {
+        CollisionMask::Circle(mirror(self.offset, facing), self.radius)
+    }
+
+    /// Every throw along with its name
+    pub fn throws(&self) -> [(&'static str, &ThrowData); 4] {
+        [
+            ("forward_throw", &self.forward_throw),
+            ("back_throw", &self.back_throw),
+            ("up_throw", &self.up_throw),
+            ("down_throw", &self.down_throw),
+        ]
+    }
+
+    pub fn throw(&self, throw: ThrowKind) -> &ThrowData {
+        match throw {
+            ThrowKind::Forward => &self.forward_throw,
+            ThrowKind::Back => &self.back_throw,
+            ThrowKind::Up => &self.up_throw,
+            ThrowKind::Down => &self.down_throw,
+        }
+    }
 }
 
 impl From<PlayerStatus> for usize {
@@ -116,6 +985,19 @@ impl From<PlayerStatus> for usize {
             PlayerStatus::Hitstun => 4,
             PlayerStatus::Blockstun => 5,
             PlayerStatus::Attacking => 6,
+            PlayerStatus::ShieldBreak => 4,
+            PlayerStatus::SpotDodge => 2,
+            PlayerStatus::Roll => 1,
+            PlayerStatus::AirDodge => 3,
+            PlayerStatus::Helpless => 3,
+            PlayerStatus::Landing => 0,
+            PlayerStatus::WallCling => 3,
+            PlayerStatus::Grabbing => 6,
+            PlayerStatus::Holding => 0,
+            PlayerStatus::Grabbed => 4,
+            PlayerStatus::Throwing => 6,
+            PlayerStatus::Dead => 10,
+            PlayerStatus::Respawning => 0,
         }
     }
 }
@@ -124,11 +1006,123 @@ impl From<PlayerStatus> for usize {
 #[storage(VecStorage)]
 pub struct CollisionData {
     pub mask: CollisionMask,
-    pub status: CollisionStatus,
-    pub repel_vector: PointFi32,
+    pub layer: CollisionLayer,
+    /// Distance per frame at which overlapping pushboxes are moved apart, at least
+    /// `MIN_REPEL_SPEED`
     pub repel_speed: Fi32,
 }
 
+/// Players on the same team. Their contacts use the friendly interactions.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+#[storage(VecStorage)]
+pub struct Team(pub u32);
+
+/// What a collider is, which decides the pairs that are tested. Combat checks attacks as
+/// hitboxes and projectiles against players' bodies as hurtboxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollisionLayer {
+    Pushbox,
+    Hurtbox,
+    Hitbox,
+    Projectile,
+    Stage,
+}
+
+impl CollisionLayer {
+    pub const ALL: [CollisionLayer; 5] = [
+        CollisionLayer::Pushbox,
+        CollisionLayer::Hurtbox,
+        CollisionLayer::Hitbox,
+        CollisionLayer::Projectile,
+        CollisionLayer::Stage,
+    ];
+}
+
+/// What a contact between two layers means
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interaction {
+    /// The pair is never tested
+    #[default]
+    Ignore,
+    /// Bodies are pushed apart
+    Push,
+    /// Reported without any physical response
+    Overlap,
+}
+
+/// Symmetric interaction matrix between layers, with a separate one for teammates
+#[derive(Debug, Clone)]
+pub struct CollisionMatrix {
+    interactions: [[Interaction; CollisionLayer::ALL.len()]; CollisionLayer::ALL.len()],
+    friendly: [[Interaction; CollisionLayer::ALL.len()]; CollisionLayer::ALL.len()],
+}
+
+impl Default for CollisionMatrix {
+    fn default() -> Self {
+        let mut matrix = CollisionMatrix {
+            interactions: Default::default(),
+            friendly: Default::default(),
+        };
+        use CollisionLayer::*;
+        for (a, b, interaction) in [
+            (Pushbox, Pushbox, Interaction::Push),
+            (Pushbox, Stage, Interaction::Push),
+            (Hurtbox, Hitbox, Interaction::Overlap),
+            (Hurtbox, Projectile, Interaction::Overlap),
+            (Hitbox, Projectile, Interaction::Overlap),
+            (Projectile, Projectile, Interaction::Overlap),
+            (Projectile, Stage, Interaction::Overlap),
+        ] {
+            matrix.set(a, b, interaction);
+        }
+        // Teammates pass through each other and can't hit each other
+        matrix.set_friendly(Pushbox, Pushbox, Interaction::Ignore);
+        matrix.set_friendly(Hurtbox, Hitbox, Interaction::Ignore);
+        matrix.set_friendly(Hurtbox, Projectile, Interaction::Ignore);
+        matrix
+    }
+}
+
+impl CollisionMatrix {
+    /// Set the interaction for both teammates and opponents
+    pub fn set(&mut self, a: CollisionLayer, b: CollisionLayer, interaction: Interaction) {
+        self.set_hostile(a, b, interaction);
+        self.set_friendly(a, b, interaction);
+    }
+
+    pub fn set_hostile(&mut self, a: CollisionLayer, b: CollisionLayer, interaction: Interaction) {
+        self.interactions[a as usize][b as usize] = interaction;
+        self.interactions[b as usize][a as usize] = interaction;
+    }
+
+    pub fn set_friendly(&mut self, a: CollisionLayer, b: CollisionLayer, interaction: Interaction) {
+        self.friendly[a as usize][b as usize] = interaction;
+        self.friendly[b as usize][a as usize] = interaction;
+    }
+
+    pub fn get(&self, a: CollisionLayer, b: CollisionLayer, teammates: bool) -> Interaction {
+        if teammates {
+            self.friendly[a as usize][b as usize]
+        } else {
+            self.interactions[a as usize][b as usize]
+        }
+    }
+}
+
+/// Emitted by `Collider` for each pair of overlapping colliders whose layers interact
+#[derive(Debug, Clone, Copy)]
+pub struct CollisionEvent {
+    pub a: Entity,
+    pub b: Entity,
+    pub interaction: Interaction,
+    /// Fraction of the frame's motion after which the pair touches, 0 if already overlapping
+    pub time_of_impact: Fi32,
+    /// Unit vector from a towards b
+    pub normal: PointFi32,
+    /// How far the two overlap along the normal at the time of impact
+    pub depth: Fi32,
+}
+
 #[derive(Debug, Default)]
 pub enum CollisionMask {
     Circle(PointFi32, Fi32),
@@ -136,10 +1130,66 @@ pub enum CollisionMask {
     Box,
 }
 
-#[derive(Debug, Default)]
-pub struct CollisionStatus(pub bool);
-
 impl CollisionMask {
+    /// Axis-aligned bounds as (min, max), if the mask can collide at all
+    pub fn bounds(&self, position: PointFi32) -> Option<(PointFi32, PointFi32)> {
+        match self {
+            CollisionMask::Circle(center, radius) => {
+                let center = position.saturating_add(*center);
+                let extent = PointFi32::new(*radius, *radius);
+                Some((center.saturating_sub(extent), center.saturating_add(extent)))
+            }
+            CollisionMask::Box => None,
+        }
+    }
+
+    /// Bounds covering the mask over a motion, for sweeping
+    pub fn swept_bounds(
+        &self,
+        position: PointFi32,
+        motion: PointFi32,
+    ) -> Option<(PointFi32, PointFi32)> {
+        let (start_min, start_max) = self.bounds(position)?;
+        let (end_min, end_max) = self.bounds(position.saturating_add(motion))?;
+        Some((
+            PointFi32::new(start_min.x.min(end_min.x), start_min.y.min(end_min.y)),
+            PointFi32::new(start_max.x.max(end_max.x), start_max.y.max(end_max.y)),
+        ))
+    }
+
+    /// Earliest fraction of a frame, from 0 to 1, at which two masks moving by the given
+    /// motions touch. The motions are sampled in steps no longer than the smaller radius,
+    /// so fast bodies can't pass through each other between frames.
+    pub fn time_of_impact(
+        &self,
+        position: PointFi32,
+        motion: PointFi32,
+        other: &CollisionMask,
+        other_position: PointFi32,
+        other_motion: PointFi32,
+    ) -> Option<Fi32> {
+        if self.check(position, other, other_position) {
+            return Some(Fi32::ZERO);
+        }
+        let (CollisionMask::Circle(_, radius), CollisionMask::Circle(_, other_radius)) =
+            (self, other)
+        else {
+            return None;
+        };
+        let step = (*radius).min(*other_radius).max(crate::MIN_SWEEP_STEP);
+        let steps = ((motion - other_motion).length() / step)
+            .ceil()
+            .to_num::<i32>()
+            .clamp(1, crate::MAX_SWEEP_STEPS);
+        (1..=steps).map(|i| Fi32::from_num(i) / steps).find(|&t| {
+            self.check(
+                position + motion * t,
+                other,
+                other_position + other_motion * t,
+            )
+        })
+    }
+
     pub fn check(
         &self,
         position: PointFi32,
@@ -151,11 +1201,9 @@ impl CollisionMask {
             match other {
                 CollisionMask::Circle(other_center, other_radius) => {
                     let adjusted_other_center = other_position + *other_center;
-                    ((adjusted_center.x - adjusted_other_center.x)
-                        * (adjusted_center.x - adjusted_other_center.x)
-                        + (adjusted_center.y - adjusted_other_center.y)
-                            * (adjusted_center.y - adjusted_other_center.y))
-                        <= (radius + other_radius) * (radius + other_radius)
+                    // Squared in i64 so far apart bodies don't overflow
+                    let reach = i64::from((*radius + *other_radius).to_bits());
+                    adjusted_center.distance_squared_wide(adjusted_other_center) <= reach * reach
                 }
                 CollisionMask::Box => false,
             }
@@ -1,4 +1,7 @@
-pub const COMMAND_BUFFER_SIZE: usize = 10;
+use crate::Fi32;
+
+// Buffered inputs, covering ten frames at MAX_TICK_RATE so every input window fits
+pub const COMMAND_BUFFER_SIZE: usize = 10 * (MAX_TICK_RATE / BASE_TICK_RATE) as usize;
 
 pub const FONT_PATH: &str = "/Users/benchen/workspace/sm/assets/fonts/Simple Signature.otf";
 
@@ -9,9 +12,88 @@ pub const JUMPING_PATH: &str = "/Users/benchen/workspace/sm/assets/Samurai/Jump.
 pub const HITSTUN_PATH: &str = "/Users/benchen/workspace/sm/assets/Samurai/Hurt.png";
 pub const BLOCKSTUN_PATH: &str = "/Users/benchen/workspace/sm/assets/Samurai/Block.png";
 pub const ATTACKING_PATH: &str = "/Users/benchen/workspace/sm/assets/Samurai/Attack_1.png";
+pub const ATTACKING_2_PATH: &str = "/Users/benchen/workspace/sm/assets/Samurai/Attack_2.png";
+pub const ATTACKING_3_PATH: &str = "/Users/benchen/workspace/sm/assets/Samurai/Attack_3.png";
 
+pub const DEAD_PATH: &str = "/Users/benchen/workspace/sm/assets/Samurai/Dead.png";
 pub const FIGHTER_DEAD_PATH: &str = "/Users/benchen/workspace/sm/assets/Fighter/Dead.png";
 
-pub const ATTACK_TOTAL_FRAMES: u32 = 18;
+// Hitlag = damage * HITLAG_MULTIPLIER + HITLAG_BASE, up to HITLAG_MAX
+pub const HITLAG_MULTIPLIER: Fi32 = Fi32::lit("0.65");
+pub const HITLAG_BASE: u32 = 4;
+pub const HITLAG_MAX: u32 = 20;
+// Distance moved by each smash DI input during hitlag
+pub const SDI_DISTANCE: Fi32 = Fi32::lit("6");
+
+// Hitstun = knockback * HITSTUN_MULTIPLIER
+pub const HITSTUN_MULTIPLIER: Fi32 = Fi32::lit("0.4");
+// Launch speed = knockback * KNOCKBACK_SPEED_MULTIPLIER
+pub const KNOCKBACK_SPEED_MULTIPLIER: Fi32 = Fi32::lit("0.3");
+// Launch speed lost per frame
+pub const KNOCKBACK_DECAY: Fi32 = Fi32::lit("0.5");
+// Tangent of the largest angle directional influence can change a launch by
+pub const DI_STRENGTH: Fi32 = Fi32::lit("0.3");
+
+// A direction pressed within this many frames of attacking gives a smash attack
+pub const SMASH_INPUT_WINDOW: usize = 4;
+
+// Shield must be pressed within this many frames before landing to L-cancel
+pub const L_CANCEL_WINDOW: usize = 7;
+
+// Crouching within this many frames before jumping gives a superjump
+pub const SUPERJUMP_WINDOW: usize = 3;
+
+// Shielding this many frames or fewer before a hit parries it
+pub const PARRY_WINDOW: u32 = 5;
+// Extra hitlag for an attacker whose hit was parried
+pub const PARRY_FREEZE: u32 = 12;
+
+// Shieldstun = damage * SHIELDSTUN_MULTIPLIER + SHIELDSTUN_BASE
+pub const SHIELDSTUN_MULTIPLIER: Fi32 = Fi32::lit("0.8");
+pub const SHIELDSTUN_BASE: u32 = 2;
+pub const SHIELD_DAMAGE_MULTIPLIER: Fi32 = Fi32::lit("1.2");
+pub const SHIELD_PUSHBACK_MULTIPLIER: Fi32 = Fi32::lit("0.6");
+// Fraction of max shield health restored after a shield break
+pub const SHIELD_BREAK_RESET: Fi32 = Fi32::lit("0.375");
+
+// Inputs needed to escape a grab = GRAB_ESCAPE_BASE + percent * GRAB_ESCAPE_MULTIPLIER
+pub const GRAB_ESCAPE_BASE: u32 = 10;
+pub const GRAB_ESCAPE_MULTIPLIER: Fi32 = Fi32::lit("0.1");
+// Speed both players are pushed apart with when a grab ends without a throw
+pub const GRAB_RELEASE_SPEED: Fi32 = Fi32::lit("8");
+
+// Reflected projectiles gain speed and damage by this factor
+pub const REFLECT_MULTIPLIER: Fi32 = Fi32::lit("1.5");
+
+// Frames between being knocked out and respawning
+pub const DEAD_FRAMES: u32 = 90;
+// Knocked out players stay this far inside the blast zone so their Dead animation is visible
+pub const KNOCKOUT_INSET: Fi32 = Fi32::lit("160");
+// Players leave the respawn platform on any input, or after this many frames
+pub const RESPAWN_PLATFORM_FRAMES: u32 = 180;
+// Invincibility after leaving the respawn platform
+pub const RESPAWN_INVINCIBLE_FRAMES: u32 = 120;
+
+// Combo counters stay on screen this many frames after the combo's last hit
+pub const COMBO_DISPLAY_FRAMES: u32 = 90;
+
+// Most meter a player can store
+pub const METER_MAX: Fi32 = Fi32::lit("100");
+// Meter gained per point of damage dealt and taken
+pub const METER_GAIN_DEALT: Fi32 = Fi32::lit("0.7");
+pub const METER_GAIN_TAKEN: Fi32 = Fi32::lit("0.5");
+
+// Overlapping pushboxes are moved apart at least this fast, even with no repel_speed
+pub const MIN_REPEL_SPEED: Fi32 = Fi32::lit("1");
+
+// Sweeps and physics substeps move at most this far, or a body's radius if larger
+pub const MIN_SWEEP_STEP: Fi32 = Fi32::lit("8");
+pub const MAX_SWEEP_STEPS: i32 = 16;
+
+// Tangent of the steepest change in slope which can be walked over between floor segments
+pub const MAX_WALK_SLOPE: Fi32 = Fi32::lit("1.5");
 
-pub const FRAME_TIME: std::time::Duration = std::time::Duration::from_nanos(1_000_000_000 / 60);
+// Ticks per second the game runs at by default, which animation timings are counted at
+pub const BASE_TICK_RATE: u32 = 60;
+// Highest tick rate the input buffer holds every input window at
+pub const MAX_TICK_RATE: u32 = 240;
@@ -37,8 +37,122 @@ impl PointFi32 {
         self.x * other.x + self.y * other.y
     }
 
+    /// Dot product in `i64` with 14 fractional bits, so it can't overflow
+    pub fn dot_wide(self, other: Self) -> i64 {
+        let x = i64::from(self.x.to_bits()) * i64::from(other.x.to_bits());
+        let y = i64::from(self.y.to_bits()) * i64::from(other.y.to_bits());
+        x.saturating_add(y)
+    }
+
+    /// Squared length in `i64` with 14 fractional bits, so it can't overflow
+    pub fn length_squared_wide(self) -> i64 {
+        self.dot_wide(self)
+    }
+
+    /// Squared distance in `i64` with 14 fractional bits. The difference is taken in `i64`
+    /// too, so points on opposite ends of the `Fi32` range only saturate.
+    pub fn distance_squared_wide(self, other: Self) -> i64 {
+        let x = i64::from(self.x.to_bits()) - i64::from(other.x.to_bits());
+        let y = i64::from(self.y.to_bits()) - i64::from(other.y.to_bits());
+        x.saturating_mul(x).saturating_add(y.saturating_mul(y))
+    }
+
+    /// Sum of two points, or `None` if either coordinate overflows
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        Some(PointFi32 {
+            x: self.x.checked_add(other.x)?,
+            y: self.y.checked_add(other.y)?,
+        })
+    }
+
+    /// Sum of two points, with each coordinate saturating at the `Fi32` range
+    pub fn saturating_add(self, other: Self) -> Self {
+        PointFi32 {
+            x: self.x.saturating_add(other.x),
+            y: self.y.saturating_add(other.y),
+        }
+    }
+
+    /// Difference of two points, with each coordinate saturating at the `Fi32` range
+    pub fn saturating_sub(self, other: Self) -> Self {
+        PointFi32 {
+            x: self.x.saturating_sub(other.x),
+            y: self.y.saturating_sub(other.y),
+        }
+    }
+
+    /// Difference of two points, or `None` if either coordinate overflows
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(PointFi32 {
+            x: self.x.checked_sub(other.x)?,
+            y: self.y.checked_sub(other.y)?,
+        })
+    }
+
+    /// The point scaled by `rhs`, or `None` if either coordinate overflows
+    pub fn checked_mul(self, rhs: Fi32) -> Option<Self> {
+        Some(PointFi32 {
+            x: self.x.checked_mul(rhs)?,
+            y: self.y.checked_mul(rhs)?,
+        })
+    }
+
+    /// Dot product, or `None` if either product or their sum overflows
+    pub fn checked_dot(self, other: Self) -> Option<Fi32> {
+        self.x
+            .checked_mul(other.x)?
+            .checked_add(self.y.checked_mul(other.y)?)
+    }
+
+    /// Length within 1/256 + |v|·2^-20 of the exact value, saturating at `Fi32::MAX`.
+    /// Nothing is squared, so it can't overflow.
+    pub fn length(self) -> Fi32 {
+        let (x, _, _) = cordic_vector(self.x.to_bits().into(), self.y.to_bits().into());
+        let length =
+            (i128::from(x) * i128::from(CORDIC_GAIN)) >> (CORDIC_FRACTION + VECTOR_SHIFT - 1);
+        to_fi32(((length + 1) >> 1) as i64)
+    }
+
+    /// Direction of the vector within 2^-16 of a turn, zero for the zero vector
+    pub fn angle(self) -> Angle {
+        Angle::atan2(self.y, self.x)
+    }
+
+    /// Rotate by an angle. Each component is within 1/256 + |v|·2^-20 of the exact value.
+    pub fn rotate(self, angle: Angle) -> PointFi32 {
+        let (cos, sin) = cordic_rotate(angle);
+        let (x, y) = (i64::from(self.x.to_bits()), i64::from(self.y.to_bits()));
+        PointFi32 {
+            x: to_fi32(round_shift(x * cos - y * sin, CORDIC_FRACTION)),
+            y: to_fi32(round_shift(x * sin + y * cos, CORDIC_FRACTION)),
+        }
+    }
+
+    /// Linear interpolation, `self` at t = 0 and `other` at t = 1
+    pub fn lerp(self, other: PointFi32, t: Fi32) -> PointFi32 {
+        self + (other - self) * t
+    }
+
+    /// Shorten the vector to at most `max` long, keeping its direction
+    pub fn clamp_length(self, max: Fi32) -> PointFi32 {
+        let length = self.length();
+        if length <= max || length.is_zero() {
+            return self;
+        }
+        let scale = |component: Fi32| {
+            to_fi32(
+                i64::from(component.to_bits()) * i64::from(max.to_bits())
+                    / i64::from(length.to_bits()),
+            )
+        };
+        PointFi32 {
+            x: scale(self.x),
+            y: scale(self.y),
+        }
+    }
+
     pub fn normalize(self) -> PointFi32 {
-        let l = (self.x * self.x + self.y * self.y).sqrt();
+        let l = self.length();
         if l.is_zero() {
             return PointFi32::new(Fi32::ZERO, Fi32::ZERO);
         }
@@ -125,3 +239,152 @@ impl MulAssign<Fi32> for PointFi32 {
         self.y *= rhs;
     }
 }
+
+/// Binary angle, 65536 to a full turn, which wraps around exactly.
+/// Angles go from +x towards +y, which is clockwise on screen because y points down.
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq, Hash)]
+pub struct Angle(pub u16);
+
+impl Angle {
+    pub const ZERO: Angle = Angle(0);
+    pub const QUARTER: Angle = Angle(1 << 14);
+    pub const HALF: Angle = Angle(1 << 15);
+
+    pub fn from_degrees<Src: ToFixed>(degrees: Src) -> Self {
+        let bits = i64::from(Fi32::from_num(degrees).to_bits());
+        Angle((bits * 65536 + 180 * 128).div_euclid(360 * 128) as u16)
+    }
+
+    pub fn from_radians(radians: Fi32) -> Self {
+        Angle(round_shift(i64::from(radians.to_bits()) * TURN_PER_RADIAN, 23) as u16)
+    }
+
+    /// Degrees in [-180, 180)
+    pub fn degrees(self) -> Fi32 {
+        to_fi32(round_shift(i64::from(self.0 as i16) * 360 * 128, 16))
+    }
+
+    /// Radians in [-π, π)
+    pub fn radians(self) -> Fi32 {
+        to_fi32(round_shift(i64::from(self.0 as i16) * RADIAN_PER_TURN, 38))
+    }
+
+    /// Within 1/256 + 2^-20 of the exact sine
+    pub fn sin(self) -> Fi32 {
+        to_fi32(round_shift(cordic_rotate(self).1, CORDIC_FRACTION - 7))
+    }
+
+    /// Within 1/256 + 2^-20 of the exact cosine
+    pub fn cos(self) -> Fi32 {
+        to_fi32(round_shift(cordic_rotate(self).0, CORDIC_FRACTION - 7))
+    }
+
+    /// Unit vector pointing along the angle
+    pub fn unit(self) -> PointFi32 {
+        PointFi32 {
+            x: self.cos(),
+            y: self.sin(),
+        }
+    }
+
+    /// Angle of the vector (x, y) within 2^-16 of a turn, zero when both are zero
+    pub fn atan2(y: Fi32, x: Fi32) -> Self {
+        let (_, _, angle) = cordic_vector(x.to_bits().into(), y.to_bits().into());
+        Angle(round_shift(angle, 16) as u16)
+    }
+}
+
+impl Add for Angle {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Angle(self.0.wrapping_add(other.0))
+    }
+}
+
+impl Sub for Angle {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Angle(self.0.wrapping_sub(other.0))
+    }
+}
+
+impl Neg for Angle {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Angle(self.0.wrapping_neg())
+    }
+}
+
+/// atan(2^-i) in 2^-32 turns
+const CORDIC_ANGLES: [i64; 24] = [
+    536870912, 316933406, 167458907, 85004756, 42667331, 21354465, 10679838, 5340245, 2670163,
+    1335087, 667544, 333772, 166886, 83443, 41722, 20861, 10430, 5215, 2608, 1304, 652, 326, 163,
+    81,
+];
+/// Fractional bits of CORDIC sines and cosines
+const CORDIC_FRACTION: u32 = 30;
+/// Product of cos(atan(2^-i)) over the iterations, which undoes the CORDIC growth
+const CORDIC_GAIN: i64 = 652032874;
+/// Vectors are scaled up by this many bits so small ones keep their precision
+const VECTOR_SHIFT: u32 = 24;
+/// 2^32 / 2π
+const TURN_PER_RADIAN: i64 = 683565276;
+/// 2π · 2^29
+const RADIAN_PER_TURN: i64 = 3373259426;
+const QUARTER_TURN: i64 = 1 << 30;
+const HALF_TURN: i64 = 1 << 31;
+
+fn round_shift(value: i64, shift: u32) -> i64 {
+    (value + (1 << (shift - 1))) >> shift
+}
+
+/// Saturate rather than wrap results which don't fit
+fn to_fi32(bits: i64) -> Fi32 {
+    Fi32::from_bits(bits.clamp(i32::MIN.into(), i32::MAX.into()) as i32)
+}
+
+/// Cosine and sine with `CORDIC_FRACTION` fractional bits
+fn cordic_rotate(angle: Angle) -> (i64, i64) {
+    let mut z = i64::from(angle.0 as i16) << 16;
+    // CORDIC only converges up to about a quarter turn, so the rest is rotated by a half turn
+    let flip = !(-QUARTER_TURN..=QUARTER_TURN).contains(&z);
+    if flip {
+        z -= HALF_TURN * z.signum();
+    }
+    let (mut x, mut y) = (CORDIC_GAIN, 0);
+    for (i, step) in CORDIC_ANGLES.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if z >= 0 {
+            (x, y, z) = (x - dx, y + dy, z - step);
+        } else {
+            (x, y, z) = (x + dx, y - dy, z + step);
+        }
+    }
+    if flip {
+        (-x, -y)
+    } else {
+        (x, y)
+    }
+}
+
+/// Rotate (x, y) onto the +x axis, returning the grown length scaled by `VECTOR_SHIFT`,
+/// the leftover y and the angle turned through in 2^-32 turns
+fn cordic_vector(x: i64, y: i64) -> (i64, i64, i64) {
+    let (mut x, mut y) = (x << VECTOR_SHIFT, y << VECTOR_SHIFT);
+    let mut z = 0;
+    if x < 0 {
+        (x, y, z) = (-x, -y, HALF_TURN);
+    }
+    for (i, step) in CORDIC_ANGLES.iter().enumerate() {
+        let (dx, dy) = (y >> i, x >> i);
+        if y < 0 {
+            (x, y, z) = (x - dx, y + dy, z - step);
+        } else {
+            (x, y, z) = (x + dx, y - dy, z + step);
+        }
+    }
+    (x, y, z)
+}
@@ -0,0 +1,161 @@
+use crate::{
+    characters::Character,
+    combat::{hitlag, launch_speed, shieldstun},
+    AttackData, Direction, Fi32, FrameData, KnockbackData, ThrowData,
+};
+
+/// Frame data for one move, derived from the character data the game runs on.
+/// Advantage assumes the first active frame connects against a 0% opponent of weight 100.
+/// Parried attackers also sit through hitlag and PARRY_FREEZE while the defender is free.
+#[derive(Debug, Clone)]
+pub struct FrameDataRow {
+    pub character: Character,
+    pub name: String,
+    pub frames: FrameData,
+    pub damage: Option<Fi32>,
+    pub on_hit: Option<i32>,
+    pub on_block: Option<i32>,
+    pub on_parry: Option<i32>,
+    /// Damage multiplier of a counter's retaliation
+    pub counter: Option<Fi32>,
+}
+
+const HEADERS: [&str; 11] = [
+    "character",
+    "move",
+    "startup",
+    "active",
+    "recovery",
+    "total",
+    "damage",
+    "on_hit",
+    "on_block",
+    "on_parry",
+    "counter",
+];
+
+/// Frame data for every character and move
+pub fn table() -> Vec<FrameDataRow> {
+    let mut rows = Vec::new();
+    for character in Character::ALL {
+        let moveset = character.moveset();
+        for (name, attack) in moveset.moves() {
+            rows.push(attack_row(character, name, attack));
+        }
+
+        let grabs = character.grabs();
+        rows.push(FrameDataRow {
+            character,
+            name: "grab".to_string(),
+            frames: grabs.frames,
+            damage: None,
+            on_hit: None,
+            on_block: None,
+            on_parry: None,
+            counter: None,
+        });
+        for (name, throw) in grabs.throws() {
+            rows.push(throw_row(character, name, throw));
+        }
+    }
+    rows
+}
+
+fn attack_row(character: Character, name: String, attack: &AttackData) -> FrameDataRow {
+    // Frames the attacker is still busy for after the first active frame
+    let remaining = (attack.frames.active + attack.frames.recovery) as i32 - 1;
+    let knockback = attack.hitboxes.first().map(|hitbox| &hitbox.knockback);
+    FrameDataRow {
+        character,
+        name,
+        frames: attack.frames,
+        damage: knockback.map(|knockback| knockback.damage),
+        on_hit: knockback.map(|knockback| hitstun(knockback) - remaining),
+        on_block: knockback.map(|knockback| shieldstun(knockback.damage) as i32 - remaining),
+        on_parry: knockback.map(|knockback| {
+            -((hitlag(knockback.damage) + crate::PARRY_FREEZE) as i32 + remaining)
+        }),
+        counter: attack.counter,
+    }
+}
+
+fn throw_row(character: Character, name: &str, throw: &ThrowData) -> FrameDataRow {
+    FrameDataRow {
+        character,
+        name: name.to_string(),
+        frames: FrameData {
+            startup: 0,
+            active: 0,
+            recovery: throw.total_frames,
+        },
+        damage: Some(throw.knockback.damage),
+        on_hit: Some(hitstun(&throw.knockback) - throw.total_frames as i32),
+        on_block: None,
+        on_parry: None,
+        counter: None,
+    }
+}
+
+fn hitstun(knockback: &KnockbackData) -> i32 {
+    let (_, hitstun) = launch_speed(
+        knockback,
+        Direction::Right,
+        knockback.damage,
+        Fi32::from_num(100),
+        None,
+    );
+    hitstun as i32
+}
+
+impl FrameDataRow {
+    fn fields(&self) -> [String; 11] {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        [
+            format!("{:?}", self.character),
+            self.name.clone(),
+            self.frames.startup.to_string(),
+            self.frames.active.to_string(),
+            self.frames.recovery.to_string(),
+            self.frames.total().to_string(),
+            optional(self.damage.map(|damage| damage.to_string())),
+            optional(self.on_hit.map(|on_hit| on_hit.to_string())),
+            optional(self.on_block.map(|on_block| on_block.to_string())),
+            optional(self.on_parry.map(|on_parry| on_parry.to_string())),
+            optional(self.counter.map(|counter| counter.to_string())),
+        ]
+    }
+}
+
+pub fn to_csv(rows: &[FrameDataRow]) -> String {
+    let mut csv = HEADERS.join(",");
+    csv.push('\n');
+    for row in rows {
+        csv.push_str(&row.fields().join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+/// Names are strings and missing values are null. Everything else is a number.
+pub fn to_json(rows: &[FrameDataRow]) -> String {
+    let objects = rows
+        .iter()
+        .map(|row| {
+            let fields = HEADERS
+                .iter()
+                .zip(row.fields())
+                .enumerate()
+                .map(|(i, (header, value))| {
+                    let value = match value {
+                        _ if i < 2 => format!("\"{}\"", value),
+                        value if value.is_empty() => "null".to_string(),
+                        value => value,
+                    };
+                    format!("\"{}\": {}", header, value)
+                })
+                .collect::<Vec<_>>();
+            format!("  {{{}}}", fields.join(", "))
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", objects.join(",\n"))
+}
@@ -0,0 +1,286 @@
+use specs::{Entities, Entity, Join, Read, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::{
+    combat::{launch_speed, take_damage},
+    keyboard_input::held_direction,
+    meter::gain_meter,
+    mirror, overflow, CollisionData, Damage, Direction, Dodges, Fi32, Grabs, Health, Hitlag, Input,
+    InputBuffer, Meter, MovementStats, PhysicsData, Player1, PlayerState, PlayerStatus, PointFi32,
+    ThrowKind, TickRate,
+};
+
+/// Connects grabs, holds grabbed players in front of their grabber, and resolves
+/// pummels, throws and mash-out escapes
+pub struct Grappling;
+
+impl<'a> System<'a> for Grappling {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, PhysicsData>,
+        ReadStorage<'a, CollisionData>,
+        WriteStorage<'a, PlayerState>,
+        ReadStorage<'a, Dodges>,
+        ReadStorage<'a, Grabs>,
+        WriteStorage<'a, Damage>,
+        ReadStorage<'a, MovementStats>,
+        ReadStorage<'a, Player1>,
+        ReadExpect<'a, InputBuffer>,
+        ReadStorage<'a, Hitlag>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, Meter>,
+        Read<'a, TickRate>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let inputs = data.9.get_all();
+        let tick_rate = *data.13;
+
+        // Victims whose grabber was interrupted are let go
+        let mut released = Vec::new();
+        for (victim, victim_state) in (&data.0, &data.3).join() {
+            overflow::track(victim);
+            if victim_state.status != PlayerStatus::Grabbed {
+                continue;
+            }
+            let held = victim_state
+                .grab
+                .and_then(|grabber| data.3.get(grabber))
+                .is_some_and(|grabber_state| {
+                    grabber_state.status == PlayerStatus::Holding
+                        && grabber_state.grab == Some(victim)
+                });
+            if !held {
+                released.push(victim);
+            }
+        }
+        for victim in released {
+            if let Some(victim_state) = data.3.get_mut(victim) {
+                victim_state.status = PlayerStatus::Idle;
+                victim_state.grab = None;
+            }
+        }
+
+        // Connect grabs, ignoring shields
+        let mut grabs = Vec::new();
+        for (grabber, grabber_physics, grabber_state, grab_data) in
+            (&data.0, &data.1, &data.3, &data.5).join()
+        {
+            overflow::track(grabber);
+            if grabber_state.status != PlayerStatus::Grabbing
+                || data.10.contains(grabber)
+                || !grab_data.frames.is_active(grabber_state.frame(&tick_rate))
+            {
+                continue;
+            }
+            let mask = grab_data.mask(grabber_state.facing);
+            let victim = (&data.0, &data.1, &data.2, &data.3)
+                .join()
+                .find(|(victim, victim_physics, victim_collision, victim_state)| {
+                    *victim != grabber
+                        && !data.10.contains(*victim)
+                        && !victim_state.is_grabbing()
+                        && !victim_state.is_airborne()
+                        && !victim_state.is_invincible()
+                        && !data
+                            .4
+                            .get(*victim)
+                            .is_some_and(|dodges| dodges.is_intangible(victim_state, &tick_rate))
+                        && !grabs.iter().any(|&(_, grabbed)| grabbed == *victim)
+                        && mask.check(
+                            grabber_physics.position,
+                            &victim_collision.mask,
+                            victim_physics.position,
+                        )
+                })
+                .map(|(victim, ..)| victim);
+            if let Some(victim) = victim {
+                grabs.push((grabber, victim));
+            }
+        }
+        for (grabber, victim) in grabs {
+            overflow::track(grabber);
+            let Some(facing) = data.3.get_mut(grabber).map(|grabber_state| {
+                grabber_state.status = PlayerStatus::Holding;
+                grabber_state.animation_counter = 0;
+                grabber_state.grab = Some(victim);
+                grabber_state.facing
+            }) else {
+                continue;
+            };
+            if let Some(victim_state) = data.3.get_mut(victim) {
+                victim_state.status = PlayerStatus::Grabbed;
+                victim_state.animation_counter = 0;
+                victim_state.grab = Some(grabber);
+                victim_state.mash = 0;
+                victim_state.knockback = PointFi32::default();
+                victim_state.facing = facing.opposite();
+            }
+        }
+
+        // Whiffed grabs end after their total frames
+        for (grabber, grabber_state, grab_data) in (&data.0, &mut data.3, &data.5).join() {
+            overflow::track(grabber);
+            if grabber_state.status == PlayerStatus::Grabbing
+                && grab_data
+                    .frames
+                    .is_finished(grabber_state.frame(&tick_rate))
+            {
+                grabber_state.status = PlayerStatus::Idle;
+            }
+        }
+
+        // Grabbed players mash to escape
+        for (victim, victim_state, _) in (&data.0, &mut data.3, &data.8).join() {
+            overflow::track(victim);
+            if victim_state.status == PlayerStatus::Grabbed {
+                victim_state.mash += inputs[0]
+                    .iter()
+                    .filter(|input| !inputs[1].contains(input))
+                    .count() as u32;
+            }
+        }
+
+        let mut holds = Vec::new();
+        for (grabber, grabber_state, grab_data) in (&data.0, &data.3, &data.5).join() {
+            if let (PlayerStatus::Holding, Some(victim)) =
+                (grabber_state.status, grabber_state.grab)
+            {
+                holds.push((grabber, victim, grab_data.clone()));
+            }
+        }
+        for (grabber, victim, grab_data) in holds {
+            overflow::track(grabber);
+            let Some(grabber_state) = data.3.get(grabber).cloned() else {
+                continue;
+            };
+            let Some(victim_state) = data.3.get(victim).cloned() else {
+                continue;
+            };
+            if victim_state.status != PlayerStatus::Grabbed || victim_state.grab != Some(grabber) {
+                end_hold(&mut data.3, grabber, None);
+                continue;
+            }
+            let percent = data.6.get(victim).map_or(Fi32::ZERO, |damage| damage.0);
+            let escape_mash =
+                crate::GRAB_ESCAPE_BASE + (percent * crate::GRAB_ESCAPE_MULTIPLIER).to_num::<u32>();
+
+            let is_player = data.8.contains(grabber);
+            let pressed = |input: Input| {
+                is_player && inputs[0].contains(&input) && !inputs[1].contains(&input)
+            };
+            let throw = if pressed(Input::Move(grabber_state.facing)) {
+                Some(ThrowKind::Forward)
+            } else if pressed(Input::Move(grabber_state.facing.opposite())) {
+                Some(ThrowKind::Back)
+            } else if pressed(Input::Up) {
+                Some(ThrowKind::Up)
+            } else if pressed(Input::Crouch) {
+                Some(ThrowKind::Down)
+            } else {
+                None
+            };
+
+            if let Some(throw) = throw {
+                let throw = grab_data.throw(throw);
+                gain_meter(&mut data.12, grabber, victim, throw.knockback.damage);
+                let percent = take_damage(
+                    data.6.get_mut(victim),
+                    data.11.get_mut(victim),
+                    throw.knockback.damage,
+                );
+                let weight = data
+                    .7
+                    .get(victim)
+                    .map_or(Fi32::from_num(100), |stats| stats.weight);
+                let held = data.8.contains(victim).then(|| held_direction(&inputs[0]));
+                let (launch, hitstun) = launch_speed(
+                    &throw.knockback,
+                    grabber_state.facing,
+                    percent,
+                    weight,
+                    held,
+                );
+                let launch = tick_rate.per_frame_velocity(launch);
+                if let Some(victim_physics) = data.1.get_mut(victim) {
+                    victim_physics.speed = launch;
+                }
+                if let Some(victim_state) = data.3.get_mut(victim) {
+                    victim_state.status = PlayerStatus::Hitstun;
+                    victim_state.stun = tick_rate.ticks(hitstun);
+                    victim_state.knockback = launch;
+                    victim_state.animation_counter = 0;
+                    victim_state.grab = None;
+                }
+                if let Some(grabber_state) = data.3.get_mut(grabber) {
+                    grabber_state.status = PlayerStatus::Throwing;
+                    grabber_state.stun = tick_rate.ticks(throw.total_frames);
+                    grabber_state.animation_counter = 0;
+                    grabber_state.grab = None;
+                }
+            } else if victim_state.mash >= escape_mash
+                || victim_state.frame(&tick_rate) > grab_data.max_hold_frames
+            {
+                end_hold(&mut data.3, grabber, Some(victim));
+                let release_speed = tick_rate.per_frame(crate::GRAB_RELEASE_SPEED);
+                for (entity, direction) in [
+                    (grabber, grabber_state.facing.opposite()),
+                    (victim, grabber_state.facing),
+                ] {
+                    if let Some(physics_data) = data.1.get_mut(entity) {
+                        physics_data.speed.x = match direction {
+                            Direction::Right => release_speed,
+                            Direction::Left => -release_speed,
+                        };
+                    }
+                }
+            } else {
+                // Pummel, using the grabber's animation counter as the cooldown
+                if pressed(Input::Attack)
+                    && grabber_state.frame(&tick_rate) >= grab_data.pummel_frames
+                {
+                    take_damage(
+                        data.6.get_mut(victim),
+                        data.11.get_mut(victim),
+                        grab_data.pummel_damage,
+                    );
+                    gain_meter(&mut data.12, grabber, victim, grab_data.pummel_damage);
+                    if let Some(grabber_state) = data.3.get_mut(grabber) {
+                        grabber_state.animation_counter = 0;
+                    }
+                }
+
+                // Hold the victim in front of the grabber
+                let Some(grabber_physics) = data.1.get_mut(grabber) else {
+                    continue;
+                };
+                grabber_physics.speed = PointFi32::default();
+                grabber_physics.acceleration = PointFi32::default();
+                let position = grabber_physics.position
+                    + mirror(
+                        PointFi32::new(grab_data.hold_distance, Fi32::ZERO),
+                        grabber_state.facing,
+                    );
+                if let Some(victim_physics) = data.1.get_mut(victim) {
+                    victim_physics.position = position;
+                    victim_physics.speed = PointFi32::default();
+                    victim_physics.acceleration = PointFi32::default();
+                }
+            }
+        }
+    }
+}
+
+/// Return the grabber, and the victim if given, to idle
+fn end_hold(
+    player_states: &mut WriteStorage<PlayerState>,
+    grabber: Entity,
+    victim: Option<Entity>,
+) {
+    for entity in std::iter::once(grabber).chain(victim) {
+        if let Some(player_state) = player_states.get_mut(entity) {
+            player_state.status = PlayerStatus::Idle;
+            player_state.animation_counter = 0;
+            player_state.grab = None;
+        }
+    }
+}
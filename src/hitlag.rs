@@ -0,0 +1,115 @@
+use specs::{
+    Component, Entities, Entity, Join, LendJoin, ReadExpect, ReadStorage, System, WriteStorage,
+};
+
+use crate::{
+    keyboard_input::held_direction, Combo, Frozen, Hitlag, Input, InputBuffer, Meter, PhysicsData,
+    Player1, PlayerState, Projectile, Shield, Sprite,
+};
+
+/// Runs before all other systems. Records the state of entities in hitlag.
+pub struct HitlagFreeze;
+
+impl<'a> System<'a> for HitlagFreeze {
+    type SystemData = (
+        WriteStorage<'a, Hitlag>,
+        ReadStorage<'a, PhysicsData>,
+        ReadStorage<'a, PlayerState>,
+        ReadStorage<'a, Sprite>,
+        ReadStorage<'a, Shield>,
+        ReadStorage<'a, Combo>,
+        ReadStorage<'a, Projectile>,
+        ReadStorage<'a, Meter>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        for (hitlag, physics_data, player_state, sprite, shield, combo, projectile, meter) in (
+            &mut data.0,
+            &data.1,
+            (&data.2).maybe(),
+            (&data.3).maybe(),
+            (&data.4).maybe(),
+            (&data.5).maybe(),
+            (&data.6).maybe(),
+            (&data.7).maybe(),
+        )
+            .join()
+        {
+            hitlag.frozen = Some(Frozen {
+                physics_data: physics_data.clone(),
+                player_state: player_state.cloned(),
+                sprite: sprite.cloned(),
+                shield: shield.cloned(),
+                combo: combo.copied(),
+                projectile: projectile.cloned(),
+                meter: meter.copied(),
+            });
+        }
+    }
+}
+
+/// Runs after all other systems. Undoes this frame's changes to the entities in hitlag
+/// themselves, so systems don't need to skip them. Systems acting on other entities from a
+/// frozen one, like hitboxes, grabs and spawning projectiles, still check for `Hitlag`.
+pub struct HitlagRestore;
+
+impl<'a> System<'a> for HitlagRestore {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, Hitlag>,
+        WriteStorage<'a, PhysicsData>,
+        WriteStorage<'a, PlayerState>,
+        WriteStorage<'a, Sprite>,
+        ReadStorage<'a, Player1>,
+        ReadExpect<'a, InputBuffer>,
+        WriteStorage<'a, Shield>,
+        WriteStorage<'a, Combo>,
+        WriteStorage<'a, Projectile>,
+        WriteStorage<'a, Meter>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let mut finished = Vec::new();
+        for (entity, hitlag) in (&data.0, &mut data.1).join() {
+            if let Some(frozen) = hitlag.frozen.take() {
+                restore(&mut data.2, entity, Some(frozen.physics_data));
+                restore(&mut data.3, entity, frozen.player_state);
+                restore(&mut data.4, entity, frozen.sprite);
+                restore(&mut data.7, entity, frozen.shield);
+                restore(&mut data.8, entity, frozen.combo);
+                restore(&mut data.9, entity, frozen.projectile);
+                restore(&mut data.10, entity, frozen.meter);
+
+                // Smash DI on each newly pressed direction
+                if hitlag.sdi && data.5.contains(entity) {
+                    let inputs = data.6.get_all();
+                    let pressed = inputs[0]
+                        .iter()
+                        .any(|input| is_direction(input) && !inputs[1].contains(input));
+                    if let (true, Some(physics_data)) = (pressed, data.2.get_mut(entity)) {
+                        physics_data.position += held_direction(&inputs[0]) * crate::SDI_DISTANCE;
+                    }
+                }
+
+                hitlag.frames = hitlag.frames.saturating_sub(1);
+            }
+            if hitlag.frames == 0 {
+                finished.push(entity);
+            }
+        }
+        for entity in finished {
+            data.1.remove(entity);
+        }
+    }
+}
+
+/// Put back a component recorded by `HitlagFreeze`
+fn restore<T: Component>(storage: &mut WriteStorage<T>, entity: Entity, component: Option<T>) {
+    if let Some(component) = component {
+        storage.insert(entity, component).unwrap();
+    }
+}
+
+fn is_direction(input: &Input) -> bool {
+    matches!(input, Input::Move(_) | Input::Up | Input::Crouch)
+}
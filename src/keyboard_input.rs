@@ -1,11 +1,12 @@
 use sdl2::keyboard::Scancode;
-use specs::{Join, ReadExpect, ReadStorage, System, WriteStorage};
+use specs::{Entities, Join, LendJoin, Read, ReadExpect, ReadStorage, System, WriteStorage};
 
 use std::collections::HashSet;
 
 use crate::{
-    Direction, Fi32, Input, InputBuffer, MovementStats, PhysicsData, Player1, PlayerState,
-    PlayerStatus,
+    mirror, overflow, AttackKind, Direction, Dodges, Fi32, Input, InputBuffer, Meter,
+    MovementStats, Moveset, PhysicsData, Player1, PlayerState, PlayerStatus, PointFi32, Ruleset,
+    Stick, TickRate, WallStats,
 };
 
 pub struct Keyboard;
@@ -17,65 +18,249 @@ impl<'a> System<'a> for Keyboard {
         WriteStorage<'a, PhysicsData>,
         ReadStorage<'a, MovementStats>,
         WriteStorage<'a, PlayerState>,
+        ReadStorage<'a, Dodges>,
+        ReadStorage<'a, Moveset>,
+        Read<'a, Ruleset>,
+        ReadStorage<'a, WallStats>,
+        ReadStorage<'a, Meter>,
+        Read<'a, TickRate>,
+        Entities<'a>,
     );
 
     fn run(&mut self, mut data: Self::SystemData) {
-        for (physics_data, movement_stats, player_state) in
-            (&mut data.2, &data.3, &mut data.4).join()
+        for (
+            entity,
+            player1,
+            physics_data,
+            movement_stats,
+            player_state,
+            dodges,
+            moveset,
+            wall_stats,
+            meter,
+        ) in (
+            &data.11,
+            data.0.maybe(),
+            &mut data.2,
+            &data.3,
+            &mut data.4,
+            &data.5,
+            &data.6,
+            &data.8,
+            data.9.maybe(),
+        )
+            .join()
         {
-            let inputs = data.1.get_all();
+            overflow::track(entity);
+            // Other characters don't take input
+            let inputs = match player1 {
+                Some(_) => data.1.get_all(),
+                None => core::array::from_fn(|_| HashSet::new()),
+            };
+            let tick_rate = &data.10;
+            let movement_stats = &movement_stats.per_tick(tick_rate);
             physics_data.acceleration.x = Fi32::ZERO;
             player_state.animation_counter += 1;
+
+            // Knocked out players are handled by Knockout
+            if player_state.status == PlayerStatus::Dead {
+                physics_data.speed = PointFi32::default();
+                physics_data.acceleration = PointFi32::default();
+                continue;
+            }
+            // Wait on the respawn platform until any input
+            if player_state.status == PlayerStatus::Respawning {
+                let pressed = inputs[0].iter().any(|input| !inputs[1].contains(input));
+                if pressed || player_state.frame(tick_rate) > crate::RESPAWN_PLATFORM_FRAMES {
+                    player_state.status = PlayerStatus::Jumping;
+                    player_state.invincible = tick_rate.ticks(crate::RESPAWN_INVINCIBLE_FRAMES);
+                } else {
+                    physics_data.speed = PointFi32::default();
+                    physics_data.acceleration = PointFi32::default();
+                    continue;
+                }
+            }
+            player_state.invincible = player_state.invincible.saturating_sub(1);
+            player_state.jab_timeout = player_state.jab_timeout.saturating_sub(1);
+            let attack_pressed =
+                inputs[0].contains(&Input::Attack) && !inputs[1].contains(&Input::Attack);
+            let grab_pressed =
+                inputs[0].contains(&Input::Grab) && !inputs[1].contains(&Input::Grab);
+            let special_pressed =
+                inputs[0].contains(&Input::Special) && !inputs[1].contains(&Input::Special);
+
+            // Smash attacks hold on their charge frame while attack is held
+            let charge = moveset
+                .get(player_state.attack)
+                .and_then(|attack| attack.charge.as_ref());
+            if let (PlayerStatus::Attacking, Some(charge)) = (player_state.status, charge) {
+                if player_state.animation_counter == tick_rate.ticks(charge.frame + 1)
+                    && inputs[0].contains(&Input::Attack)
+                    && player_state.charge < tick_rate.ticks(charge.max_frames)
+                {
+                    player_state.animation_counter -= 1;
+                    player_state.charge += 1;
+                }
+            }
+
+            // Recover from hitstun, blockstun and shield break
+            if player_state.is_stunned() {
+                player_state.stun = player_state.stun.saturating_sub(1);
+                if player_state.stun == 0 {
+                    player_state.status = if player_state.status == PlayerStatus::Blockstun
+                        && inputs[0].contains(&Input::Shield)
+                    {
+                        // Still holding shield, so the parry window stays closed
+                        player_state.animation_counter = tick_rate.ticks(crate::PARRY_WINDOW);
+                        PlayerStatus::Blocking
+                    } else if physics_data.ground.is_none() {
+                        PlayerStatus::Jumping
+                    } else {
+                        PlayerStatus::Idle
+                    };
+                }
+            }
+
+            // Start or finish dodges
+            if let Some(dodge) = dodges.get(player_state.status) {
+                if player_state.frame(tick_rate) > dodge.total_frames {
+                    player_state.status = match player_state.status {
+                        PlayerStatus::AirDodge => PlayerStatus::Helpless,
+                        _ => PlayerStatus::Idle,
+                    };
+                }
+            } else if let Some((status, direction)) = dodge_input(&inputs, player_state) {
+                player_state.status = status;
+                player_state.dodge_direction = direction;
+                player_state.animation_counter = 0;
+            }
+
             match &inputs[0] {
                 h if h.is_empty()
                     || player_state.status == PlayerStatus::Attacking
-                    || h.contains(&Input::Attack) =>
+                    || player_state.is_stunned()
+                    || player_state.is_dodging()
+                    || player_state.is_grabbing()
+                    || h.contains(&Input::Attack)
+                    || h.contains(&Input::Special)
+                    || h.contains(&Input::Grab)
+                    || (h.contains(&Input::Shield) && !h.contains(&Input::Jump)) =>
                 {
-                    physics_data.acceleration.x = match player_state.status {
-                        PlayerStatus::Jumping => match physics_data.speed.x {
-                            x_speed if x_speed > movement_stats.air_max_speed => {
-                                -((movement_stats.air_acceleration).min(x_speed))
-                            }
-                            x_speed if x_speed < -(movement_stats.air_max_speed) => {
-                                (movement_stats.air_acceleration).min(-x_speed)
-                            }
-                            _ => Fi32::ZERO,
-                        },
-                        _ => match physics_data.speed.x {
-                            x_speed if x_speed.is_positive() => {
-                                -(movement_stats.friction.min(physics_data.speed.x))
+                    physics_data.acceleration.x =
+                        if player_state.is_airborne() || physics_data.ground.is_none() {
+                            match physics_data.speed.x {
+                                x_speed if x_speed > movement_stats.air_max_speed => {
+                                    -((movement_stats.air_acceleration).min(x_speed))
+                                }
+                                x_speed if x_speed < -(movement_stats.air_max_speed) => {
+                                    (movement_stats.air_acceleration).min(-x_speed)
+                                }
+                                _ => Fi32::ZERO,
                             }
-                            x_speed if x_speed.is_negative() => {
-                                movement_stats.friction.min(-physics_data.speed.x)
+                        } else {
+                            match physics_data.speed.x {
+                                x_speed if x_speed.is_positive() => {
+                                    -(movement_stats.friction.min(physics_data.speed.x))
+                                }
+                                x_speed if x_speed.is_negative() => {
+                                    movement_stats.friction.min(-physics_data.speed.x)
+                                }
+                                _ => Fi32::ZERO,
                             }
-                            _ => Fi32::ZERO,
-                        },
-                    };
+                        };
                     dbg!(physics_data.speed.x);
-                    if h.contains(&Input::Attack) {
-                        if player_state.status != PlayerStatus::Attacking {
+                    if player_state.is_stunned()
+                        || player_state.is_dodging()
+                        || player_state.is_grabbing()
+                        || player_state.status == PlayerStatus::Helpless
+                    {
+                        // Locked out of actions
+                    } else if player_state.status == PlayerStatus::Attacking {
+                        // Continue the jab string within the cancel window
+                        if let AttackKind::Jab(step) = player_state.attack {
+                            let in_cancel_window = moveset
+                                .jab
+                                .get(step)
+                                .and_then(|jab| jab.cancel_window)
+                                .is_some_and(|(start, end)| {
+                                    (start..=end).contains(&player_state.frame(tick_rate))
+                                });
+                            if attack_pressed && in_cancel_window && step + 1 < moveset.jab.len() {
+                                start_attack(player_state, AttackKind::Jab(step + 1));
+                            }
+                        }
+                    } else if attack_pressed || special_pressed {
+                        // Attacking out of shield drops the shield
+                        let airborne = player_state.is_airborne() || physics_data.ground.is_none();
+                        let attack = choose_attack(
+                            &inputs,
+                            player_state,
+                            moveset,
+                            special_pressed,
+                            airborne,
+                            tick_rate.input_window(crate::SMASH_INPUT_WINDOW),
+                        );
+                        let attack = metered_attack(attack, h, moveset, meter);
+                        start_attack(player_state, attack);
+                    } else if grab_pressed
+                        && [
+                            PlayerStatus::Idle,
+                            PlayerStatus::Running,
+                            PlayerStatus::Blocking,
+                        ]
+                        .contains(&player_state.status)
+                    {
+                        // Grabbing out of shield drops the shield
+                        player_state.status = PlayerStatus::Grabbing;
+                        player_state.animation_counter = 0;
+                    } else if h.contains(&Input::Shield)
+                        && [
+                            PlayerStatus::Idle,
+                            PlayerStatus::Running,
+                            PlayerStatus::Blocking,
+                        ]
+                        .contains(&player_state.status)
+                    {
+                        // Raising shield opens the parry window
+                        if player_state.status != PlayerStatus::Blocking {
                             player_state.animation_counter = 0;
                         }
-                        player_state.status = PlayerStatus::Attacking;
-                    };
+                        player_state.status = PlayerStatus::Blocking;
+                    }
                 }
                 h if h.contains(&Input::Jump) => match player_state.status {
-                    PlayerStatus::Idle | PlayerStatus::Running => {
+                    // Jumping out of shield is allowed
+                    PlayerStatus::Idle | PlayerStatus::Running | PlayerStatus::Blocking => {
                         player_state.status = PlayerStatus::Jumping;
-                        physics_data.speed.y = if inputs[1].contains(&Input::Crouch)
-                            || inputs[2].contains(&Input::Crouch)
-                            || inputs[3].contains(&Input::Crouch)
-                        {
-                            -(movement_stats.superjump_power)
-                        } else {
-                            -(movement_stats.jump_power)
-                        };
+                        let superjump_window = tick_rate.input_window(crate::SUPERJUMP_WINDOW);
+                        physics_data.speed.y =
+                            if (1..=superjump_window).any(|i| inputs[i].contains(&Input::Crouch)) {
+                                -(movement_stats.superjump_power)
+                            } else {
+                                -(movement_stats.jump_power)
+                            };
+                    }
+                    // Wall jump when jumping into a wall
+                    PlayerStatus::Jumping | PlayerStatus::WallCling
+                        if wall_stats.can_wall_jump && !inputs[1].contains(&Input::Jump) =>
+                    {
+                        if let Some(wall) = physics_data.wall_contact {
+                            let away = wall.opposite();
+                            let wall_jump_speed = tick_rate.per_frame(wall_stats.wall_jump_speed);
+                            physics_data.speed.x = match away {
+                                Direction::Right => wall_jump_speed,
+                                Direction::Left => -wall_jump_speed,
+                            };
+                            physics_data.speed.y = -tick_rate.per_frame(wall_stats.wall_jump_power);
+                            player_state.facing = away;
+                            player_state.status = PlayerStatus::Jumping;
+                        }
                     }
                     _ => (),
                 },
                 h if h.contains(&Input::Move(Direction::Left)) => {
                     physics_data.acceleration.x = match player_state.status {
-                        PlayerStatus::Jumping => {
+                        PlayerStatus::Jumping | PlayerStatus::Helpless => {
                             if physics_data.speed.x > -(movement_stats.air_max_speed) {
                                 -(movement_stats.air_acceleration)
                             } else {
@@ -93,7 +278,7 @@ impl<'a> System<'a> for Keyboard {
                 }
                 h if h.contains(&Input::Move(Direction::Right)) => {
                     physics_data.acceleration.x = match player_state.status {
-                        PlayerStatus::Jumping => {
+                        PlayerStatus::Jumping | PlayerStatus::Helpless => {
                             if physics_data.speed.x < movement_stats.air_max_speed {
                                 movement_stats.air_acceleration
                             } else {
@@ -115,22 +300,97 @@ impl<'a> System<'a> for Keyboard {
                 _ => (),
             };
 
-            // Clamp to max_speed
-            physics_data.speed.x = physics_data
-                .speed
-                .x
-                .clamp(-movement_stats.max_speed, movement_stats.max_speed);
+            // Clamp to max_speed unless being launched
+            if player_state.knockback == PointFi32::default() {
+                physics_data.speed.x = physics_data
+                    .speed
+                    .x
+                    .clamp(-movement_stats.max_speed, movement_stats.max_speed);
+            }
 
             // Gravity
-            if player_state.status == PlayerStatus::Jumping || physics_data.position.y < Fi32::ZERO
-            {
+            if player_state.is_airborne() || physics_data.ground.is_none() {
                 physics_data.acceleration.y = movement_stats.gravity;
             } else {
                 physics_data.acceleration.y = Fi32::ZERO;
             }
 
+            // Knockback decays by a fixed amount each frame
+            if player_state.knockback != PointFi32::default() {
+                let direction = player_state.knockback.normalize();
+                let decay = tick_rate.per_frame_squared(crate::KNOCKBACK_DECAY);
+                let decayed = if player_state.knockback.dot(direction) <= decay {
+                    PointFi32::default()
+                } else {
+                    player_state.knockback - direction * decay
+                };
+                if player_state.status == PlayerStatus::Hitstun {
+                    physics_data.acceleration.x = Fi32::ZERO;
+                }
+                physics_data.acceleration += decayed - player_state.knockback;
+                player_state.knockback = decayed;
+            }
+
+            // Moves can launch their user
+            if player_state.status == PlayerStatus::Attacking {
+                let impulse = moveset
+                    .get(player_state.attack)
+                    .and_then(|attack| attack.impulse);
+                if let Some((frame, speed)) = impulse {
+                    if player_state.animation_counter == tick_rate.ticks(frame) {
+                        let speed = tick_rate.per_frame_velocity(speed);
+                        physics_data.speed = mirror(speed, player_state.facing);
+                    }
+                }
+            }
+
+            // Dodges follow a fixed travel curve, ignoring gravity. Air dodges fall again once
+            // the curve runs out rather than hanging in the air.
+            if let Some(dodge) = dodges.get(player_state.status) {
+                match dodge.travel.get(player_state.frame(tick_rate) as usize) {
+                    Some(&travel) => {
+                        physics_data.speed =
+                            player_state.dodge_direction * tick_rate.per_frame(travel);
+                        physics_data.acceleration = PointFi32::new(0, 0);
+                    }
+                    None if player_state.status == PlayerStatus::AirDodge => (),
+                    None => {
+                        physics_data.speed = PointFi32::default();
+                        physics_data.acceleration = PointFi32::new(0, 0);
+                    }
+                }
+            }
+
+            // Cling to walls when holding into them while falling
+            let holding_into_wall = physics_data
+                .wall_contact
+                .is_some_and(|wall| inputs[0].contains(&Input::Move(wall)));
+            if player_state.status == PlayerStatus::Jumping
+                && wall_stats.can_cling
+                && holding_into_wall
+                && physics_data.speed.y.is_positive()
+            {
+                player_state.status = PlayerStatus::WallCling;
+                player_state.animation_counter = 0;
+                if let Some(wall) = physics_data.wall_contact {
+                    player_state.facing = wall.opposite();
+                }
+            }
+            if player_state.status == PlayerStatus::WallCling {
+                if holding_into_wall && player_state.frame(tick_rate) <= wall_stats.cling_frames {
+                    let slide_speed = tick_rate.per_frame(wall_stats.cling_slide_speed);
+                    physics_data.speed = PointFi32::new(Fi32::ZERO, slide_speed);
+                    physics_data.acceleration = PointFi32::new(0, 0);
+                } else {
+                    player_state.status = PlayerStatus::Jumping;
+                }
+            }
+
             // Update player state if grounded
-            if ![PlayerStatus::Jumping, PlayerStatus::Attacking].contains(&player_state.status) {
+            if [PlayerStatus::Idle, PlayerStatus::Running].contains(&player_state.status)
+                || (player_state.status == PlayerStatus::Blocking
+                    && !inputs[0].contains(&Input::Shield))
+            {
                 if physics_data.speed.x.is_zero() && physics_data.speed.y.is_zero() {
                     player_state.status = PlayerStatus::Idle;
                 } else if !physics_data.speed.x.is_zero() {
@@ -140,12 +400,34 @@ impl<'a> System<'a> for Keyboard {
 
             // Check if attack finished
             if player_state.status == PlayerStatus::Attacking
-                && player_state.animation_counter > crate::ATTACK_TOTAL_FRAMES
+                && moveset
+                    .get(player_state.attack)
+                    .is_none_or(|attack| attack.frames.is_finished(player_state.frame(tick_rate)))
             {
-                player_state.status = PlayerStatus::Idle;
+                let airborne = player_state.is_airborne() || physics_data.ground.is_none();
+                let helpless = moveset
+                    .get(player_state.attack)
+                    .is_some_and(|attack| attack.helpless);
+                player_state.status = match player_state.attack {
+                    // The string resets after its last step
+                    AttackKind::Jab(step) => {
+                        if step + 1 < moveset.jab.len() {
+                            player_state.jab_timeout = tick_rate.ticks(moveset.jab_timeout);
+                        }
+                        PlayerStatus::Idle
+                    }
+                    _ if airborne && helpless => PlayerStatus::Helpless,
+                    _ if airborne => PlayerStatus::Jumping,
+                    _ => PlayerStatus::Idle,
+                };
             }
 
-            if inputs[0].contains(&Input::Move(Direction::Left))
+            if player_state.is_dodging()
+                || player_state.is_grabbing()
+                || player_state.status == PlayerStatus::WallCling
+            {
+                // Dodges, grabs and wall clings keep their facing
+            } else if inputs[0].contains(&Input::Move(Direction::Left))
                 && physics_data.speed.x.is_negative()
             {
                 player_state.facing = Direction::Left;
@@ -155,16 +437,55 @@ impl<'a> System<'a> for Keyboard {
                 player_state.facing = Direction::Right;
             }
 
-            if (physics_data.position.y + physics_data.speed.y).is_positive() {
+            // Physics puts players on the platform they land on
+            if physics_data.ground.is_some() && !physics_data.speed.y.is_negative() {
                 physics_data.speed.y = Fi32::ZERO;
                 physics_data.acceleration.y = Fi32::ZERO;
-                physics_data.position.y = Fi32::ZERO;
-                if player_state.status == PlayerStatus::Jumping {
-                    player_state.status = if physics_data.speed.x == Fi32::ZERO {
-                        PlayerStatus::Idle
-                    } else {
-                        PlayerStatus::Running
+                let landing_lag = match player_state.status {
+                    PlayerStatus::Jumping | PlayerStatus::WallCling => {
+                        Some(movement_stats.landing_lag)
                     }
+                    PlayerStatus::AirDodge | PlayerStatus::Helpless => Some(dodges.landing_lag),
+                    PlayerStatus::Attacking
+                        if matches!(player_state.attack, AttackKind::Aerial(_)) =>
+                    {
+                        let aerial = moveset
+                            .get(player_state.attack)
+                            .and_then(|attack| attack.aerial.as_ref());
+                        match aerial {
+                            Some(aerial) if !aerial.auto_cancels(player_state.frame(tick_rate)) => {
+                                let l_cancelled = data.7.l_cancel
+                                    && (0..tick_rate.input_window(crate::L_CANCEL_WINDOW)).any(
+                                        |i| {
+                                            inputs[i].contains(&Input::Shield)
+                                                && !inputs[i + 1].contains(&Input::Shield)
+                                        },
+                                    );
+                                if l_cancelled {
+                                    Some(aerial.landing_lag / 2)
+                                } else {
+                                    Some(aerial.landing_lag)
+                                }
+                            }
+                            _ => Some(movement_stats.landing_lag),
+                        }
+                    }
+                    _ => None,
+                };
+                match landing_lag.map(|landing_lag| tick_rate.ticks(landing_lag)) {
+                    Some(0) => {
+                        player_state.status = if physics_data.speed.x == Fi32::ZERO {
+                            PlayerStatus::Idle
+                        } else {
+                            PlayerStatus::Running
+                        }
+                    }
+                    Some(landing_lag) => {
+                        player_state.status = PlayerStatus::Landing;
+                        player_state.stun = landing_lag;
+                        player_state.animation_counter = 0;
+                    }
+                    None => (),
                 }
             }
 
@@ -173,6 +494,151 @@ impl<'a> System<'a> for Keyboard {
     }
 }
 
+fn start_attack(player_state: &mut PlayerState, attack: AttackKind) {
+    player_state.animation_counter = 0;
+    player_state.charge = 0;
+    player_state.attack_hits.clear();
+    player_state.attack = attack;
+    player_state.status = PlayerStatus::Attacking;
+}
+
+/// Pick a move from the move table. Grounded directions pressed within
+/// `smash_window` buffered inputs of attacking give smash attacks, held ones give tilts.
+/// Back on the ground or with a special turns the player around.
+fn choose_attack(
+    inputs: &[HashSet<Input>],
+    player_state: &mut PlayerState,
+    moveset: &Moveset,
+    special: bool,
+    airborne: bool,
+    smash_window: usize,
+) -> AttackKind {
+    let mut stick = stick(&inputs[0], player_state.facing);
+    if airborne && !special {
+        return AttackKind::Aerial(stick);
+    }
+    if stick == Stick::Back {
+        player_state.facing = player_state.facing.opposite();
+        stick = Stick::Forward;
+    }
+    if special {
+        return AttackKind::Special(stick);
+    }
+    let stick_input = match stick {
+        Stick::Forward => Input::Move(player_state.facing),
+        Stick::Up => Input::Up,
+        Stick::Down => Input::Crouch,
+        Stick::Neutral | Stick::Back => {
+            return match player_state.attack {
+                AttackKind::Jab(step)
+                    if player_state.jab_timeout > 0 && step + 1 < moveset.jab.len() =>
+                {
+                    AttackKind::Jab(step + 1)
+                }
+                // Characters without a jab attack with their forward tilt
+                _ if moveset.jab.is_empty() => AttackKind::Tilt(Stick::Forward),
+                _ => AttackKind::Jab(0),
+            };
+        }
+    };
+    let smash = (0..smash_window)
+        .any(|i| inputs[i].contains(&stick_input) && !inputs[i + 1].contains(&stick_input));
+    if smash {
+        AttackKind::Smash(stick)
+    } else {
+        AttackKind::Tilt(stick)
+    }
+}
+
+/// Specials become supers with grab held, and neutral specials become EX with
+/// attack held, when the player has the meter to pay for them
+fn metered_attack(
+    attack: AttackKind,
+    input: &HashSet<Input>,
+    moveset: &Moveset,
+    meter: Option<&Meter>,
+) -> AttackKind {
+    let upgrade = match attack {
+        AttackKind::Special(_) if input.contains(&Input::Grab) => AttackKind::Super,
+        AttackKind::Special(Stick::Neutral) if input.contains(&Input::Attack) => AttackKind::Ex,
+        _ => return attack,
+    };
+    let affordable = moveset
+        .get(upgrade)
+        .and_then(|upgrade| upgrade.meter)
+        .is_some_and(|cost| meter.is_some_and(|meter| meter.can_afford(&cost)));
+    if affordable {
+        upgrade
+    } else {
+        attack
+    }
+}
+
+/// Direction of the held input relative to the given facing
+fn stick(input: &HashSet<Input>, facing: Direction) -> Stick {
+    if input.contains(&Input::Up) {
+        Stick::Up
+    } else if input.contains(&Input::Crouch) {
+        Stick::Down
+    } else if input.contains(&Input::Move(facing)) {
+        Stick::Forward
+    } else if input.contains(&Input::Move(facing.opposite())) {
+        Stick::Back
+    } else {
+        Stick::Neutral
+    }
+}
+
+/// Get the dodge, if any, started by the latest inputs along with its direction.
+/// Grounded dodges need shield and a direction, with one of them newly pressed.
+/// Air dodges are started by pressing shield and travel in the held direction.
+fn dodge_input(
+    inputs: &[HashSet<Input>],
+    player_state: &PlayerState,
+) -> Option<(PlayerStatus, PointFi32)> {
+    let shield_pressed = inputs[0].contains(&Input::Shield) && !inputs[1].contains(&Input::Shield);
+    let pressed = |input: Input| {
+        inputs[0].contains(&input) && (shield_pressed || !inputs[1].contains(&input))
+    };
+    match player_state.status {
+        PlayerStatus::Idle | PlayerStatus::Running | PlayerStatus::Blocking
+            if inputs[0].contains(&Input::Shield) =>
+        {
+            if pressed(Input::Crouch) {
+                Some((PlayerStatus::SpotDodge, PointFi32::new(0, 0)))
+            } else if pressed(Input::Move(Direction::Left)) {
+                Some((PlayerStatus::Roll, PointFi32::new(-1, 0)))
+            } else if pressed(Input::Move(Direction::Right)) {
+                Some((PlayerStatus::Roll, PointFi32::new(1, 0)))
+            } else {
+                None
+            }
+        }
+        PlayerStatus::Jumping if shield_pressed => {
+            Some((PlayerStatus::AirDodge, held_direction(&inputs[0])))
+        }
+        _ => None,
+    }
+}
+
+/// Unit vector of the held direction, or zero if no direction is held
+pub fn held_direction(input: &HashSet<Input>) -> PointFi32 {
+    let mut direction = PointFi32::new(0, 0);
+    if input.contains(&Input::Move(Direction::Left)) {
+        direction.x -= Fi32::ONE;
+    }
+    if input.contains(&Input::Move(Direction::Right)) {
+        direction.x += Fi32::ONE;
+    }
+    if input.contains(&Input::Up) {
+        direction.y -= Fi32::ONE;
+    }
+    if input.contains(&Input::Crouch) {
+        direction.y += Fi32::ONE;
+    }
+    direction.normalize()
+}
+
 /// Controls: Map keyboard inputs to game inputs
 pub fn get_input(keyboard_state: &sdl2::keyboard::KeyboardState) -> HashSet<Input> {
     let mut input: HashSet<Input> = keyboard_state
@@ -181,8 +647,12 @@ pub fn get_input(keyboard_state: &sdl2::keyboard::KeyboardState) -> HashSet<Inpu
             Scancode::Left | Scancode::A => Some(Input::Move(Direction::Left)),
             Scancode::Right | Scancode::D => Some(Input::Move(Direction::Right)),
             Scancode::Down | Scancode::S => Some(Input::Crouch),
-            Scancode::Up | Scancode::W | Scancode::Space => Some(Input::Jump),
+            Scancode::Up | Scancode::W => Some(Input::Up),
+            Scancode::Space => Some(Input::Jump),
             Scancode::U => Some(Input::Attack),
+            Scancode::I => Some(Input::Shield),
+            Scancode::O => Some(Input::Grab),
+            Scancode::P => Some(Input::Special),
             Scancode::Escape | Scancode::X => Some(Input::Quit),
             _ => None,
         })
@@ -197,7 +667,7 @@ pub fn get_input(keyboard_state: &sdl2::keyboard::KeyboardState) -> HashSet<Inpu
         input.remove(&Input::Move(Direction::Right));
     }
     // Down + Up = Up
-    if input.contains(&Input::Crouch) && input.contains(&Input::Jump) {
+    if input.contains(&Input::Crouch) && input.contains(&Input::Up) {
         input.remove(&Input::Crouch);
     }
 
@@ -0,0 +1,98 @@
+use specs::{Entities, Join, LendJoin, Read, System, Write, WriteStorage};
+
+use crate::{
+    overflow, Damage, Fi32, Health, MatchState, PhysicsData, PlayerState, PlayerStatus, PointFi32,
+    Stage, Stocks, TickRate,
+};
+
+/// Knocks out players who leave the blast zone or run out of health, respawns them
+/// while they have stocks left, and ends the match when one player remains
+pub struct Knockout;
+
+impl<'a> System<'a> for Knockout {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, PhysicsData>,
+        WriteStorage<'a, PlayerState>,
+        WriteStorage<'a, Stocks>,
+        WriteStorage<'a, Health>,
+        WriteStorage<'a, Damage>,
+        Read<'a, Stage>,
+        Write<'a, MatchState>,
+        Read<'a, TickRate>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let stage = &data.6;
+        for (entity, physics_data, player_state, stocks, health, damage) in (
+            &data.0,
+            &mut data.1,
+            &mut data.2,
+            &mut data.3,
+            (&mut data.4).maybe(),
+            (&mut data.5).maybe(),
+        )
+            .join()
+        {
+            overflow::track(entity);
+            if player_state.status == PlayerStatus::Dead {
+                // Players without stocks stay down
+                player_state.stun = player_state.stun.saturating_sub(1);
+                if player_state.stun > 0 || stocks.0 == 0 {
+                    continue;
+                }
+                physics_data.position = stage.respawn;
+                physics_data.ground = None;
+                physics_data.speed = PointFi32::default();
+                physics_data.acceleration = PointFi32::default();
+                player_state.status = PlayerStatus::Respawning;
+                player_state.animation_counter = 0;
+                if let Some(damage) = damage {
+                    damage.0 = Fi32::ZERO;
+                }
+                if let Some(health) = health {
+                    health.current = health.max;
+                }
+                continue;
+            }
+
+            let out_of_bounds = stage
+                .blast_zone
+                .is_some_and(|blast_zone| !blast_zone.contains(physics_data.position));
+            let out_of_health = health.is_some_and(|health| health.current <= Fi32::ZERO);
+            if !out_of_bounds && !out_of_health {
+                continue;
+            }
+            stocks.0 = stocks.0.saturating_sub(1);
+            // Keep the body on screen so the Dead animation can be seen
+            if let Some(blast_zone) = stage.blast_zone {
+                physics_data.position =
+                    blast_zone.clamp(physics_data.position, crate::KNOCKOUT_INSET);
+            }
+            physics_data.ground = None;
+            physics_data.speed = PointFi32::default();
+            physics_data.acceleration = PointFi32::default();
+            player_state.status = PlayerStatus::Dead;
+            player_state.stun = data.8.ticks(crate::DEAD_FRAMES);
+            player_state.animation_counter = 0;
+            player_state.knockback = PointFi32::default();
+            player_state.invincible = 0;
+            player_state.grab = None;
+        }
+
+        if data.7.over {
+            return;
+        }
+        let remaining = (&data.0, &data.2, &data.3)
+            .join()
+            .filter(|(_, player_state, stocks)| {
+                stocks.0 > 0 || player_state.status != PlayerStatus::Dead
+            })
+            .map(|(entity, ..)| entity)
+            .collect::<Vec<_>>();
+        if remaining.len() <= 1 {
+            data.7.over = true;
+            data.7.winner = remaining.first().copied();
+        }
+    }
+}
@@ -4,10 +4,24 @@ mod constants;
 pub use constants::*;
 mod fi32;
 pub use fi32::*;
+mod stage;
+pub use stage::*;
 
-pub mod player_animator;
 pub mod animator;
+pub mod characters;
+pub mod collider;
+pub mod combat;
+pub mod frame_data;
+pub mod grab;
+pub mod hitlag;
 pub mod keyboard_input;
+pub mod knockout;
+pub mod meter;
+pub mod overflow;
 pub mod physics;
-pub mod collider;
+pub mod platform;
+pub mod player_animator;
+pub mod projectile;
 pub mod renderer;
+pub mod shield;
+pub mod simulation;
@@ -1,15 +1,30 @@
 use sdl2::image::{InitFlag, LoadTexture};
 use sdl2::pixels::Color;
-use sdl2::rect::Rect;
 use specs::prelude::World;
-use specs::{Builder, DispatcherBuilder, WorldExt};
+use specs::shrev::EventChannel;
+use specs::{Builder, WorldExt};
 
-use sm::{
-    CollisionData, CollisionMask, CollisionStatus, Direction, Fi32, MovementStats, PhysicsData,
-    Player1, PlayerState, PlayerStatus, PointFi32, Sprite,
-};
+use sm::characters::Character;
+use sm::{Direction, Fi32, Player1, PointFi32};
 
 fn main() -> Result<(), String> {
+    // `sm frame-data [csv|json]` prints the frame data table instead of starting the game
+    let args = std::env::args().collect::<Vec<_>>();
+    if args.get(1).map(String::as_str) == Some("frame-data") {
+        let rows = sm::frame_data::table();
+        match args.get(2).map(String::as_str) {
+            None | Some("csv") => print!("{}", sm::frame_data::to_csv(&rows)),
+            Some("json") => print!("{}", sm::frame_data::to_json(&rows)),
+            Some(format) => return Err(format!("Unknown frame data format: {}", format)),
+        }
+        return Ok(());
+    }
+
+    // Overflows panic in debug builds, so point out which system and entity caused them
+    if cfg!(debug_assertions) {
+        sm::overflow::install_diagnostics();
+    }
+
     // Initialize SDL2
     let sdl_context = sdl2::init()?;
     let video_subsystem = sdl_context.video()?;
@@ -39,6 +54,9 @@ fn main() -> Result<(), String> {
         sm::BLOCKSTUN_PATH,
         sm::ATTACKING_PATH,
         sm::FIGHTER_DEAD_PATH,
+        sm::ATTACKING_2_PATH,
+        sm::ATTACKING_3_PATH,
+        sm::DEAD_PATH,
     ];
     let textures = texture_paths.map(|path| {
         texture_creator
@@ -46,110 +64,106 @@ fn main() -> Result<(), String> {
             .unwrap_or_else(|_| panic!("Failed to load texture: {}", path))
     });
 
-    let mut dispatcher = DispatcherBuilder::new()
-        .with(sm::keyboard_input::Keyboard, "Keyboard", &[])
-        .with(sm::collider::Collider, "Collider", &["Keyboard"])
-        .with(sm::physics::Physics, "Physics", &["Collider"])
-        .with(
-            sm::player_animator::PlayerAnimator,
-            "PlayerAnimator",
-            &["Physics"],
-        )
-        .with(sm::animator::Animator, "Animator", &["PlayerAnimator"])
-        .build();
+    let mut dispatcher = sm::simulation::dispatcher();
 
     let mut world = World::new();
     world.insert(sm::InputBuffer::new());
     world.insert(sm::Framerate(1));
+    world.insert(sm::TickRate::default());
+    world.insert(sm::DebugOverlay(cfg!(debug_assertions)));
+    world.insert(sm::Ruleset {
+        l_cancel: true,
+        stocks: 3,
+        stamina: None,
+        combo: sm::ComboRules::default(),
+        meter: true,
+        teams: 0,
+    });
+    world.insert(sm::MatchState::default());
+    world.insert(sm::Stage {
+        walls: vec![
+            // At the far end of the raised ledge
+            sm::Wall {
+                x: Fi32::from_num(-600),
+                top: Fi32::from_num(-400),
+                bottom: Fi32::from_num(-80),
+                normal: Direction::Right,
+            },
+            sm::Wall {
+                x: Fi32::from_num(460),
+                top: Fi32::from_num(-320),
+                bottom: Fi32::ZERO,
+                normal: Direction::Left,
+            },
+        ],
+        platforms: vec![
+            // A ramp up to a raised ledge on the left
+            sm::Platform::segment(PointFi32::new(-600, -80), PointFi32::new(-400, -80)),
+            sm::Platform::segment(PointFi32::new(-400, -80), PointFi32::new(-160, 0)),
+            sm::Platform::segment(PointFi32::new(-160, 0), PointFi32::new(600, 0)),
+            // Drifts across the middle of the stage and back
+            sm::Platform::moving(
+                Fi32::from_num(80),
+                vec![
+                    sm::Keyframe {
+                        frame: 0,
+                        position: PointFi32::new(-200, -140),
+                    },
+                    sm::Keyframe {
+                        frame: 240,
+                        position: PointFi32::new(200, -140),
+                    },
+                    sm::Keyframe {
+                        frame: 480,
+                        position: PointFi32::new(-200, -140),
+                    },
+                ],
+            ),
+        ],
+        frame: 0,
+        blast_zone: Some(sm::BlastZone {
+            left: Fi32::from_num(-600),
+            right: Fi32::from_num(600),
+            top: Fi32::from_num(-520),
+            bottom: Fi32::from_num(400),
+        }),
+        respawn: PointFi32::new(0, -240),
+    });
     dispatcher.setup(&mut world);
+    // Contacts from the latest frame, for drawing
+    let mut contact_reader = world
+        .write_resource::<EventChannel<sm::CollisionEvent>>()
+        .register_reader();
+    let mut contacts = Vec::new();
 
-    world
-        .create_entity()
-        .with(Player1)
-        .with(PhysicsData {
-            position: PointFi32::new(0, 0),
-            speed: PointFi32::new(0, 0),
-            acceleration: PointFi32::new(0, 0),
-        })
-        .with(Sprite {
-            spritesheet: 0,
-            current: Rect::new(0, 0, 128, 128),
-            wrap: 1024,
-            flip: false,
-            counter: 0,
-            animation_rate: 5,
-            glow: false,
-        })
-        .with(CollisionData {
-            mask: CollisionMask::Circle(PointFi32::new(0, 0), Fi32::from_num(36.0)),
-            status: CollisionStatus(false),
-            repel_vector: PointFi32::new(0, 0),
-            repel_speed: Fi32::from_num(3.0),
-        })
-        .with(MovementStats {
-            max_speed: Fi32::from_num(17),
-            acceleration: Fi32::from_num(2.5),
-            friction: Fi32::from_num(1.2),
-            gravity: Fi32::from_num(1.8),
-            jump_power: Fi32::from_num(22),
-            superjump_power: Fi32::from_num(30),
-            air_acceleration: Fi32::from_num(1),
-            air_max_speed: Fi32::from_num(10),
-        })
-        .with(PlayerState {
-            status: PlayerStatus::Idle,
-            facing: Direction::Right,
-            animation_counter: 0,
-        })
-        .build();
-
-    world
-        .create_entity()
-        .with(PhysicsData {
-            position: PointFi32::new(200, 0),
-            speed: PointFi32::new(0, 0),
-            acceleration: PointFi32::new(0, 0),
-        })
-        .with(Sprite {
-            spritesheet: 7,
-            current: Rect::new(0, 0, 128, 128),
-            wrap: 384,
-            flip: false,
-            counter: 0,
-            animation_rate: 60,
-            glow: false,
-        })
-        .with(CollisionData {
-            mask: CollisionMask::Circle(PointFi32::new(0, 0), Fi32::from_num(36.0)),
-            status: CollisionStatus(false),
-            repel_vector: PointFi32::new(0, 0),
-            repel_speed: Fi32::ZERO,
-        })
-        .build();
-
-    world
-        .create_entity()
-        .with(PhysicsData {
-            position: PointFi32::new(360, 0),
-            speed: PointFi32::new(0, 0),
-            acceleration: PointFi32::new(0, 0),
-        })
-        .with(Sprite {
-            spritesheet: 7,
-            current: Rect::new(0, 0, 128, 128),
-            wrap: 384,
-            flip: true,
-            counter: 0,
-            animation_rate: 60,
-            glow: false,
-        })
-        .with(CollisionData {
-            mask: CollisionMask::Circle(PointFi32::new(0, 0), Fi32::from_num(36.0)),
-            status: CollisionStatus(false),
-            repel_vector: PointFi32::new(0, 0),
-            repel_speed: Fi32::ZERO,
-        })
-        .build();
+    // Player 1 against two idle opponents
+    let ruleset: sm::Ruleset = *world.read_resource();
+    let characters = [
+        (Character::Samurai, PointFi32::new(0, 0), Direction::Right),
+        (Character::Fighter, PointFi32::new(200, 0), Direction::Left),
+        (Character::Shinobi, PointFi32::new(360, 0), Direction::Left),
+    ];
+    for (i, (character, position, facing)) in characters.into_iter().enumerate() {
+        let mut builder = character
+            .build(world.create_entity(), position, facing)
+            .with(sm::Stocks(ruleset.stocks));
+        if let Some(stamina) = ruleset.stamina {
+            builder = builder.with(sm::Health {
+                current: stamina,
+                max: stamina,
+            });
+        }
+        if ruleset.meter {
+            builder = builder.with(sm::Meter::new(sm::METER_MAX));
+        }
+        if let Some(team) = ruleset.team(i) {
+            builder = builder.with(team);
+        }
+        if i == 0 {
+            builder = builder.with(Player1);
+        }
+        builder.build();
+    }
 
     canvas.present();
 
@@ -163,9 +177,38 @@ fn main() -> Result<(), String> {
         frame_time_accumulator += prev_time.elapsed();
 
         prev_time = std::time::Instant::now();
-        while frame_time_accumulator >= sm::FRAME_TIME {
+        loop {
+            let frame_time = world.read_resource::<sm::TickRate>().frame_time();
+            if frame_time_accumulator < frame_time {
+                break;
+            }
             // Handle events
-            event_pump.pump_events();
+            for event in event_pump.poll_iter() {
+                match event {
+                    sdl2::event::Event::KeyDown {
+                        scancode: Some(sdl2::keyboard::Scancode::F1),
+                        repeat: false,
+                        ..
+                    } => {
+                        let mut debug_overlay = world.write_resource::<sm::DebugOverlay>();
+                        debug_overlay.0 = !debug_overlay.0;
+                    }
+                    // Toggle quarter speed slow motion for practicing in the lab
+                    sdl2::event::Event::KeyDown {
+                        scancode: Some(sdl2::keyboard::Scancode::F2),
+                        repeat: false,
+                        ..
+                    } => {
+                        let mut tick_rate = world.write_resource::<sm::TickRate>();
+                        tick_rate.speed = if tick_rate.speed == Fi32::ONE {
+                            Fi32::from_num(0.25)
+                        } else {
+                            Fi32::ONE
+                        };
+                    }
+                    _ => (),
+                }
+            }
             let keyboard_state = event_pump.keyboard_state();
             let input = sm::keyboard_input::get_input(&keyboard_state);
             if input.contains(&sm::Input::Quit) {
@@ -179,7 +222,12 @@ fn main() -> Result<(), String> {
             // Update state
             dispatcher.dispatch(&world);
             world.maintain();
-            frame_time_accumulator -= sm::FRAME_TIME;
+            contacts = world
+                .read_resource::<EventChannel<sm::CollisionEvent>>()
+                .read(&mut contact_reader)
+                .copied()
+                .collect();
+            frame_time_accumulator -= frame_time;
         }
 
         // Render
@@ -192,6 +240,7 @@ fn main() -> Result<(), String> {
             &textures,
             &font,
             &world,
+            &contacts,
         )?;
 
         // Count frames
@@ -0,0 +1,77 @@
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::{
+    overflow, Fi32, Hitlag, Meter, Moveset, PhysicsData, PlayerState, PlayerStatus, TickRate,
+};
+
+/// Spends meter on moves as they start, freezing everyone else for supers.
+/// Meter is gained in `Combat` and `Grappling` through `gain_meter`.
+pub struct Metering;
+
+impl<'a> System<'a> for Metering {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, PlayerState>,
+        ReadStorage<'a, Moveset>,
+        WriteStorage<'a, Meter>,
+        WriteStorage<'a, Hitlag>,
+        ReadStorage<'a, PhysicsData>,
+        Read<'a, TickRate>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let mut freezes = Vec::new();
+        for (user, player_state, moveset, meter) in (&data.0, &data.1, &data.2, &mut data.3).join()
+        {
+            overflow::track(user);
+            if player_state.status != PlayerStatus::Attacking
+                || player_state.animation_counter != 0
+                || data.4.contains(user)
+            {
+                continue;
+            }
+            let Some(cost) = moveset
+                .get(player_state.attack)
+                .and_then(|attack| attack.meter)
+            else {
+                continue;
+            };
+            meter.current = (meter.current - cost.cost).max(Fi32::ZERO);
+            if cost.freeze > 0 {
+                freezes.push((user, data.6.ticks(cost.freeze)));
+            }
+        }
+
+        for (user, freeze) in freezes {
+            overflow::track(user);
+            let frozen = (&data.0, &data.5)
+                .join()
+                .map(|(entity, _)| entity)
+                .filter(|&entity| entity != user)
+                .collect::<Vec<_>>();
+            for entity in frozen {
+                match data.4.get_mut(entity) {
+                    Some(hitlag) => hitlag.frames = hitlag.frames.max(freeze),
+                    None => {
+                        data.4.insert(entity, Hitlag::new(freeze, false)).unwrap();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Both sides of a hit build meter, if they have any
+pub fn gain_meter(
+    meters: &mut WriteStorage<Meter>,
+    attacker: Entity,
+    victim: Entity,
+    damage: Fi32,
+) {
+    if let Some(meter) = meters.get_mut(attacker) {
+        meter.gain(damage * crate::METER_GAIN_DEALT);
+    }
+    if let Some(meter) = meters.get_mut(victim) {
+        meter.gain(damage * crate::METER_GAIN_TAKEN);
+    }
+}
@@ -0,0 +1,45 @@
+use std::{any::type_name, cell::Cell, panic};
+
+use specs::{Entity, System, World};
+
+thread_local! {
+    /// The system running on this thread and the entity it's working on
+    static CONTEXT: Cell<Option<(&'static str, Option<Entity>)>> = const { Cell::new(None) };
+}
+
+/// Runs a system with its name recorded, so overflow panics inside it say where they came from
+pub struct Traced<S>(pub S);
+
+impl<'a, S: System<'a>> System<'a> for Traced<S> {
+    type SystemData = S::SystemData;
+
+    fn run(&mut self, data: Self::SystemData) {
+        let previous = CONTEXT.replace(Some((type_name::<S>(), None)));
+        self.0.run(data);
+        CONTEXT.set(previous);
+    }
+
+    fn setup(&mut self, world: &mut World) {
+        self.0.setup(world);
+    }
+}
+
+/// Record the entity the current system is working on
+pub fn track(entity: Entity) {
+    CONTEXT.set(CONTEXT.get().map(|(system, _)| (system, Some(entity))));
+}
+
+/// Report the system and entity alongside panics, which in debug builds include `Fi32` overflows
+pub fn install_diagnostics() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        match CONTEXT.get() {
+            Some((system, Some(entity))) => {
+                eprintln!("Panicked in {} on entity {:?}", system, entity)
+            }
+            Some((system, None)) => eprintln!("Panicked in {}", system),
+            None => (),
+        }
+        default_hook(info);
+    }));
+}
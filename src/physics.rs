@@ -1,17 +1,122 @@
-use specs::{Join, System, WriteStorage};
+use specs::{Entities, Join, LendJoin, Read, ReadStorage, System, WriteStorage};
 
-use crate::PhysicsData;
+use crate::{
+    overflow, CollisionData, CollisionMask, Fi32, PhysicsData, PointFi32, Stage, TickRate,
+};
 
 pub struct Physics;
 
 impl<'a> System<'a> for Physics {
-    type SystemData = WriteStorage<'a, PhysicsData>;
+    type SystemData = (
+        WriteStorage<'a, PhysicsData>,
+        ReadStorage<'a, CollisionData>,
+        Read<'a, Stage>,
+        Entities<'a>,
+        Read<'a, TickRate>,
+    );
 
     fn run(&mut self, mut data: Self::SystemData) {
-        for physics_data in (&mut data).join() {
-            physics_data.speed.x += physics_data.acceleration.x;
-            physics_data.speed.y += physics_data.acceleration.y;
-            physics_data.position = physics_data.position.offset(physics_data.speed.x, physics_data.speed.y);
+        let substeps = data.4.substeps.max(1) as i32;
+        for (entity, physics_data, collision_data) in
+            (&data.3, &mut data.0, (&data.1).maybe()).join()
+        {
+            overflow::track(entity);
+            let radius = match collision_data.map(|collision_data| &collision_data.mask) {
+                Some(CollisionMask::Circle(_, radius)) => *radius,
+                _ => Fi32::ZERO,
+            };
+            physics_data.wall_contact = None;
+            for substep in 0..substeps {
+                physics_data.speed.x += share(physics_data.acceleration.x, substep, substeps);
+                physics_data.speed.y += share(physics_data.acceleration.y, substep, substeps);
+                integrate(physics_data, radius, &data.2, substep, substeps);
+            }
+        }
+    }
+}
+
+/// The `step`th of `steps` parts of a value, which add up to exactly the value
+fn share(value: Fi32, step: i32, steps: i32) -> Fi32 {
+    value * (step + 1) / steps - value * step / steps
+}
+
+/// Move a body by its speed over the `substep`th of `substeps` parts of a frame, keeping it out
+/// of walls and on the floor
+fn integrate(
+    physics_data: &mut PhysicsData,
+    radius: Fi32,
+    stage: &Stage,
+    substep: i32,
+    substeps: i32,
+) {
+    // Players on the ground walk along its surface
+    let platform = physics_data
+        .ground
+        .and_then(|ground| stage.platforms.get(ground));
+    let walking = platform.filter(|_| !physics_data.speed.y.is_negative());
+    let velocity = match walking {
+        Some(platform) => {
+            let along = platform.direction();
+            along * physics_data.speed.dot(along)
+        }
+        None => physics_data.speed,
+    };
+    let movement = PointFi32 {
+        x: share(velocity.x, substep, substeps),
+        y: share(velocity.y, substep, substeps),
+    };
+
+    // Sub-step fast movement so walls can't be skipped over in a single frame
+    let steps = (movement.length() / radius.max(crate::MIN_SWEEP_STEP))
+        .ceil()
+        .to_num::<i32>()
+        .clamp(1, crate::MAX_SWEEP_STEPS);
+    let start = physics_data.position;
+    for step in 0..steps {
+        let offset = PointFi32 {
+            x: share(movement.x, step, steps),
+            y: share(movement.y, step, steps),
+        };
+        // Bodies stop at the edge of the Fi32 range rather than wrapping around to the other side
+        let Some(position) = physics_data.position.checked_add(offset) else {
+            physics_data.speed = PointFi32::default();
+            break;
+        };
+        physics_data.position = position;
+        physics_data.wall_contact = stage
+            .resolve_walls(&mut physics_data.position, &mut physics_data.speed, radius)
+            .or(physics_data.wall_contact);
+    }
+
+    // Stick to the floor while walking, stepping onto the next segment past the end of one,
+    // and keep a platform's momentum when launched or walking off it
+    match (physics_data.ground, platform, walking) {
+        (Some(ground), Some(platform), Some(_)) => {
+            let floor = if platform.spans(physics_data.position.x) {
+                Some(ground)
+            } else {
+                stage.next_floor(ground, physics_data.position, movement)
+            };
+            if let Some(floor) = floor {
+                physics_data.ground = Some(floor);
+                physics_data.position.y = stage.platforms[floor].height_at(physics_data.position.x);
+                physics_data.speed.y = Fi32::ZERO;
+            } else {
+                physics_data.speed += platform.velocity;
+                physics_data.ground = None;
+            }
+        }
+        (_, Some(platform), _) => {
+            physics_data.speed += platform.velocity;
+            physics_data.ground = None;
+        }
+        _ => {
+            if let Some(ground) = stage.landing(start, physics_data.position - start) {
+                physics_data.ground = Some(ground);
+                physics_data.position.y =
+                    stage.platforms[ground].height_at(physics_data.position.x);
+                physics_data.speed.y = Fi32::ZERO;
+            }
         }
     }
 }
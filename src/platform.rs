@@ -0,0 +1,38 @@
+use specs::{Entities, Join, Read, System, Write, WriteStorage};
+
+use crate::{overflow, PhysicsData, Stage, TickRate};
+
+/// Moves platforms along their paths and carries the entities standing on them.
+/// Runs after hitlag is restored so frozen entities stay on their platforms.
+pub struct MovePlatforms;
+
+impl<'a> System<'a> for MovePlatforms {
+    type SystemData = (
+        Write<'a, Stage>,
+        WriteStorage<'a, PhysicsData>,
+        Read<'a, TickRate>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let stage = &mut *data.0;
+        stage.frame = stage.frame.wrapping_add(1);
+        for platform in &mut stage.platforms {
+            let Some(position) = platform.path_position_at(stage.frame, &data.2) else {
+                continue;
+            };
+            platform.velocity = position - platform.position;
+            platform.position = position;
+        }
+
+        for (entity, physics_data) in (&data.3, &mut data.1).join() {
+            overflow::track(entity);
+            let platform = physics_data
+                .ground
+                .and_then(|ground| stage.platforms.get(ground));
+            if let Some(platform) = platform {
+                physics_data.position += platform.velocity;
+            }
+        }
+    }
+}
@@ -1,14 +1,21 @@
-use specs::{Join, ReadStorage, System, WriteStorage};
+use specs::{Join, LendJoin, ReadStorage, System, WriteStorage};
 
-use crate::{PhysicsData, Sprite, PlayerState, PlayerStatus, Direction, Fi32};
+use crate::{Direction, Fi32, Moveset, PhysicsData, PlayerState, PlayerStatus, Sprite};
 
 pub struct PlayerAnimator;
 
 impl<'a> System<'a> for PlayerAnimator {
-    type SystemData = (WriteStorage<'a, Sprite>, ReadStorage<'a, PhysicsData>, WriteStorage<'a, PlayerState>);
+    type SystemData = (
+        WriteStorage<'a, Sprite>,
+        ReadStorage<'a, PhysicsData>,
+        WriteStorage<'a, PlayerState>,
+        ReadStorage<'a, Moveset>,
+    );
 
     fn run(&mut self, mut data: Self::SystemData) {
-        for (sprite, physics_data, player_state) in (&mut data.0, &data.1, &mut data.2).join() {
+        for (sprite, physics_data, player_state, moveset) in
+            (&mut data.0, &data.1, &mut data.2, (&data.3).maybe()).join()
+        {
             sprite.wrap = match player_state.status {
                 PlayerStatus::Idle => 768,
                 PlayerStatus::Running => 1024,
@@ -17,15 +24,47 @@ impl<'a> System<'a> for PlayerAnimator {
                 PlayerStatus::Hitstun => 256,
                 PlayerStatus::Blockstun => 256,
                 PlayerStatus::Attacking => 768,
+                PlayerStatus::ShieldBreak => 256,
+                PlayerStatus::SpotDodge => 256,
+                PlayerStatus::Roll => 1024,
+                PlayerStatus::AirDodge => 1536,
+                PlayerStatus::Helpless => 1536,
+                PlayerStatus::Landing => 768,
+                PlayerStatus::WallCling => 256,
+                PlayerStatus::Grabbing => 768,
+                PlayerStatus::Holding => 768,
+                PlayerStatus::Grabbed => 256,
+                PlayerStatus::Throwing => 768,
+                PlayerStatus::Dead => 384,
+                PlayerStatus::Respawning => 768,
             };
             sprite.animation_rate = match player_state.status {
                 PlayerStatus::Idle => 5,
-                PlayerStatus::Running => if physics_data.speed.x.abs() > Fi32::from_num(6) { 3 } else { 5 },
+                PlayerStatus::Running => {
+                    if physics_data.speed.x.abs() > Fi32::from_num(6) {
+                        3
+                    } else {
+                        5
+                    }
+                }
                 PlayerStatus::Blocking => 5,
                 PlayerStatus::Jumping => 1,
                 PlayerStatus::Hitstun => 3,
                 PlayerStatus::Blockstun => 2,
                 PlayerStatus::Attacking => 3,
+                PlayerStatus::ShieldBreak => 12,
+                PlayerStatus::SpotDodge => 4,
+                PlayerStatus::Roll => 2,
+                PlayerStatus::AirDodge => 2,
+                PlayerStatus::Helpless => 8,
+                PlayerStatus::Landing => 3,
+                PlayerStatus::WallCling => 10,
+                PlayerStatus::Grabbing => 2,
+                PlayerStatus::Holding => 5,
+                PlayerStatus::Grabbed => 6,
+                PlayerStatus::Throwing => 3,
+                PlayerStatus::Dead => 10,
+                PlayerStatus::Respawning => 5,
             };
 
             sprite.flip = match player_state.facing {
@@ -35,9 +74,29 @@ impl<'a> System<'a> for PlayerAnimator {
 
             let old_spritesheet = sprite.spritesheet;
             sprite.spritesheet = player_state.status.into();
+
+            // Each attack has its own animation
+            let attack = moveset.and_then(|moveset| moveset.get(player_state.attack));
+            if let (PlayerStatus::Attacking, Some(attack)) = (player_state.status, attack) {
+                sprite.spritesheet = attack.animation;
+                sprite.wrap = attack.wrap;
+                sprite.animation_rate = attack.animation_rate;
+                if player_state.animation_counter == 0 {
+                    sprite.current.set_x(0);
+                    sprite.counter = 0;
+                }
+            }
+
             if old_spritesheet != sprite.spritesheet {
                 sprite.current.set_x(0);
             }
+
+            // The Dead animation plays once, holding on its last frame
+            if player_state.status == PlayerStatus::Dead
+                && sprite.current.x() as u32 + sprite.current.width() >= sprite.wrap
+            {
+                sprite.counter = 0;
+            }
         }
     }
 }
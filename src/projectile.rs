@@ -0,0 +1,250 @@
+use sdl2::rect::Rect;
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, WriteStorage};
+
+use crate::{
+    mirror, overflow, Damage, Deflection, Fi32, Health, Hitlag, Moveset, PhysicsData, PlayerState,
+    PlayerStatus, PointFi32, Projectile, Sprite, TickRate,
+};
+
+/// Spawns projectiles from moves, ages and despawns them, and resolves clashes,
+/// reflects and absorbs. Hits on players are resolved by `Combat`.
+pub struct Projectiles;
+
+impl<'a> System<'a> for Projectiles {
+    type SystemData = (
+        Entities<'a>,
+        WriteStorage<'a, PhysicsData>,
+        WriteStorage<'a, Sprite>,
+        WriteStorage<'a, Projectile>,
+        ReadStorage<'a, PlayerState>,
+        ReadStorage<'a, Moveset>,
+        ReadStorage<'a, Hitlag>,
+        WriteStorage<'a, Damage>,
+        WriteStorage<'a, Health>,
+        Read<'a, TickRate>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let tick_rate = *data.9;
+        // Age projectiles, despawning them at the end of their lifetime or on hitting the stage
+        for (entity, projectile, physics_data) in (&data.0, &mut data.3, &data.1).join() {
+            overflow::track(entity);
+            if projectile.is_destroyed() {
+                continue;
+            }
+            projectile.lifetime -= 1;
+            if physics_data.wall_contact.is_some() || physics_data.ground.is_some() {
+                projectile.lifetime = 0;
+            }
+            if projectile.is_destroyed() {
+                data.0.delete(entity).unwrap();
+            }
+        }
+
+        // Spawn projectiles from moves
+        let mut spawns = Vec::new();
+        for (owner, physics_data, player_state, moveset) in
+            (&data.0, &data.1, &data.4, &data.5).join()
+        {
+            overflow::track(owner);
+            if player_state.status != PlayerStatus::Attacking || data.6.contains(owner) {
+                continue;
+            }
+            let Some(projectile_data) = moveset
+                .get(player_state.attack)
+                .and_then(|attack| attack.projectile.as_ref())
+            else {
+                continue;
+            };
+            if player_state.animation_counter != tick_rate.ticks(projectile_data.spawn_frame) {
+                continue;
+            }
+            let count = (&data.3)
+                .join()
+                .filter(|projectile| {
+                    !projectile.is_destroyed()
+                        && projectile.owner == owner
+                        && projectile.source == player_state.attack
+                })
+                .count();
+            if count < projectile_data.max_count {
+                spawns.push((
+                    physics_data.position + mirror(projectile_data.offset, player_state.facing),
+                    Projectile {
+                        owner,
+                        source: player_state.attack,
+                        facing: player_state.facing,
+                        lifetime: tick_rate.ticks(projectile_data.lifetime),
+                        data: projectile_data.clone(),
+                    },
+                ));
+            }
+        }
+        for (position, projectile) in spawns {
+            let entity = data.0.create();
+            data.1
+                .insert(
+                    entity,
+                    PhysicsData {
+                        position,
+                        speed: mirror(
+                            tick_rate.per_frame_velocity(projectile.data.speed),
+                            projectile.facing,
+                        ),
+                        acceleration: PointFi32::new(
+                            Fi32::ZERO,
+                            tick_rate.per_frame_squared(projectile.data.gravity),
+                        ),
+                        wall_contact: None,
+                        ground: None,
+                    },
+                )
+                .unwrap();
+            if let Some((spritesheet, wrap, animation_rate)) = projectile.data.animation {
+                let size = (projectile.data.radius * 2).to_num();
+                data.2
+                    .insert(
+                        entity,
+                        Sprite {
+                            spritesheet,
+                            current: Rect::new(0, 0, size, size),
+                            wrap,
+                            flip: projectile.facing.into(),
+                            counter: 0,
+                            animation_rate,
+                        },
+                    )
+                    .unwrap();
+            }
+            data.3.insert(entity, projectile).unwrap();
+        }
+
+        // Projectiles from different owners clash, destroying the weaker one or both if even
+        let live = (&data.0, &data.1, &data.3)
+            .join()
+            .filter(|(_, _, projectile)| !projectile.is_destroyed())
+            .map(|(entity, physics_data, projectile)| {
+                (entity, physics_data.position, projectile.clone())
+            })
+            .collect::<Vec<_>>();
+        let mut destroyed = Vec::new();
+        for (i, (a, a_position, a_projectile)) in live.iter().enumerate() {
+            overflow::track(*a);
+            for (b, b_position, b_projectile) in &live[i + 1..] {
+                if a_projectile.owner == b_projectile.owner
+                    || !a_projectile
+                        .mask()
+                        .check(*a_position, &b_projectile.mask(), *b_position)
+                {
+                    continue;
+                }
+                let a_damage = a_projectile.data.knockback.damage;
+                let b_damage = b_projectile.data.knockback.damage;
+                if a_damage <= b_damage {
+                    destroyed.push(*a);
+                }
+                if b_damage <= a_damage {
+                    destroyed.push(*b);
+                }
+            }
+        }
+
+        // Reflect or absorb projectiles with deflectors, and destroy them with hitboxes
+        let users = (&data.0, &data.1, &data.4, &data.5)
+            .join()
+            .filter(|(user, _, player_state, _)| {
+                player_state.status == PlayerStatus::Attacking && !data.6.contains(*user)
+            })
+            .filter_map(|(user, physics_data, player_state, moveset)| {
+                let attack = moveset.get(player_state.attack)?.clone();
+                Some((user, physics_data.position, player_state.clone(), attack))
+            })
+            .collect::<Vec<_>>();
+        for (user, position, player_state, attack) in &users {
+            let (user, player_state) = (*user, player_state);
+            overflow::track(user);
+            let frame = player_state.frame(&tick_rate);
+            let deflector = attack
+                .deflector
+                .as_ref()
+                .filter(|deflector| (deflector.active.0..=deflector.active.1).contains(&frame));
+            for (entity, projectile_position, projectile) in &live {
+                if projectile.owner == user || destroyed.contains(entity) {
+                    continue;
+                }
+                let deflected = deflector.filter(|deflector| {
+                    deflector.mask(player_state.facing).check(
+                        *position,
+                        &projectile.mask(),
+                        *projectile_position,
+                    )
+                });
+                if let Some(deflector) = deflected {
+                    match deflector.deflection {
+                        Deflection::Reflect => {
+                            if let Some(projectile) = data.3.get_mut(*entity) {
+                                reflect(projectile, user, player_state, &tick_rate);
+                            }
+                            if let Some(projectile_physics) = data.1.get_mut(*entity) {
+                                projectile_physics.speed = PointFi32::new(
+                                    -projectile_physics.speed.x,
+                                    projectile_physics.speed.y,
+                                ) * crate::REFLECT_MULTIPLIER;
+                            }
+                            if let Some(sprite) = data.2.get_mut(*entity) {
+                                sprite.flip = player_state.facing.into();
+                            }
+                        }
+                        Deflection::Absorb => {
+                            if let Some(damage) = data.7.get_mut(user) {
+                                damage.0 =
+                                    (damage.0 - projectile.data.knockback.damage).max(Fi32::ZERO);
+                            }
+                            if let Some(health) = data.8.get_mut(user) {
+                                health.current = (health.current
+                                    + projectile.data.knockback.damage)
+                                    .min(health.max);
+                            }
+                            destroyed.push(*entity);
+                        }
+                    }
+                    continue;
+                }
+                let clashed = attack.hitboxes.iter().any(|hitbox| {
+                    attack.frames.is_active(frame)
+                        && hitbox.knockback.damage >= projectile.data.knockback.damage
+                        && hitbox.mask(player_state.facing).check(
+                            *position,
+                            &projectile.mask(),
+                            *projectile_position,
+                        )
+                });
+                if clashed {
+                    destroyed.push(*entity);
+                }
+            }
+        }
+
+        for entity in destroyed {
+            if let Some(projectile) = data.3.get_mut(entity) {
+                if !projectile.is_destroyed() {
+                    projectile.lifetime = 0;
+                    data.0.delete(entity).unwrap();
+                }
+            }
+        }
+    }
+}
+
+/// Hand a projectile over to the reflector, sending it back with extra damage
+fn reflect(
+    projectile: &mut Projectile,
+    reflector: Entity,
+    reflector_state: &PlayerState,
+    tick_rate: &TickRate,
+) {
+    projectile.owner = reflector;
+    projectile.facing = reflector_state.facing;
+    projectile.lifetime = tick_rate.ticks(projectile.data.lifetime);
+    projectile.data.knockback.damage *= crate::REFLECT_MULTIPLIER;
+}
@@ -1,16 +1,35 @@
 use sdl2::{
     gfx::primitives::DrawRenderer,
     pixels::Color,
-    rect::Rect,
+    rect::{Point, Rect},
     render::{Texture, TextureCreator, WindowCanvas},
     ttf::Font,
     video::WindowContext,
 };
-use specs::{Join, ReadStorage, World, WorldExt};
+use specs::{Entities, Join, LendJoin, ReadStorage, World, WorldExt};
 
-use crate::{Framerate, PhysicsData, PointFi32, Sprite};
+use crate::{
+    CollisionData, CollisionEvent, CollisionMask, Combo, Damage, DebugOverlay, Dodges, Fi32,
+    Framerate, Health, MatchState, Meter, Moveset, PhysicsData, PlayerState, PlayerStatus,
+    PointFi32, Projectile, Shield, Sprite, Stage, Stocks, TickRate,
+};
 
-pub type SystemData<'a> = (ReadStorage<'a, PhysicsData>, ReadStorage<'a, Sprite>);
+pub type SystemData<'a> = (
+    ReadStorage<'a, PhysicsData>,
+    ReadStorage<'a, Sprite>,
+    ReadStorage<'a, PlayerState>,
+    ReadStorage<'a, Shield>,
+    ReadStorage<'a, CollisionData>,
+    ReadStorage<'a, Dodges>,
+    ReadStorage<'a, Damage>,
+    ReadStorage<'a, Projectile>,
+    ReadStorage<'a, Moveset>,
+    ReadStorage<'a, Stocks>,
+    ReadStorage<'a, Health>,
+    ReadStorage<'a, Combo>,
+    ReadStorage<'a, Meter>,
+    Entities<'a>,
+);
 
 pub fn render(
     canvas: &mut WindowCanvas,
@@ -19,21 +38,67 @@ pub fn render(
     textures: &[Texture],
     font: &Font,
     world: &World,
+    contacts: &[CollisionEvent],
 ) -> Result<(), String> {
     canvas.set_draw_color(color);
     canvas.clear();
 
     let (width, height) = canvas.output_size()?;
     let data = world.system_data::<SystemData>();
+    let tick_rate: TickRate = *world.read_resource();
+
+    // Draw the stage
+    let stage = world.read_resource::<Stage>();
+    for wall in &stage.walls {
+        let top = PointFi32::new(wall.x, wall.top) + PointFi32::new(width / 2, height / 2);
+        let bottom = PointFi32::new(wall.x, wall.bottom) + PointFi32::new(width / 2, height / 2);
+        canvas.thick_line(
+            top.x.to_num(),
+            top.y.to_num(),
+            bottom.x.to_num(),
+            bottom.y.to_num(),
+            6,
+            Color::RGB(120, 120, 120),
+        )?;
+    }
 
-    for (physics_data, sprite) in (&data.0, &data.1).join() {
+    // Draw platforms at the feet of the players standing on them
+    for platform in &stage.platforms {
+        let offset = PointFi32::new(width / 2, height / 2 + 40);
+        let left = platform.position + platform.left + offset;
+        let right = platform.position + platform.right + offset;
+        canvas.thick_line(
+            left.x.to_num(),
+            left.y.to_num(),
+            right.x.to_num(),
+            right.y.to_num(),
+            6,
+            Color::RGB(120, 120, 120),
+        )?;
+    }
+
+    // Draw respawn platforms under respawning players
+    for (physics_data, player_state) in (&data.0, &data.2).join() {
+        if player_state.status != PlayerStatus::Respawning {
+            continue;
+        }
+        let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2 + 40);
+        let (x, y): (i16, i16) = (screen_position.x.to_num(), screen_position.y.to_num());
+        canvas.thick_line(x - 40, y, x + 40, y, 4, Color::RGB(230, 230, 240))?;
+    }
+
+    for (entity, physics_data, sprite) in (&data.13, &data.0, &data.1).join() {
         let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2);
         let screen_rect = Rect::from_center(
             screen_position,
             sprite.current.width(),
             sprite.current.height(),
         );
-        if sprite.glow {
+        // Ring entities touching something
+        if contacts
+            .iter()
+            .any(|contact| contact.a == entity || contact.b == entity)
+        {
             canvas.aa_circle(
                 screen_position.x.to_num(),
                 screen_position.y.to_num(),
@@ -51,6 +116,110 @@ pub fn render(
             false,
         )?;
     }
+    // Draw projectiles which have no sprite
+    for (physics_data, projectile, ()) in (&data.0, &data.7, !&data.1).join() {
+        let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2);
+        canvas.filled_circle(
+            screen_position.x.to_num(),
+            screen_position.y.to_num(),
+            projectile.data.radius.to_num(),
+            Color::RGB(200, 200, 210),
+        )?;
+    }
+    // Draw shields sized to the remaining shield health
+    for (physics_data, player_state, shield) in (&data.0, &data.2, &data.3).join() {
+        if !player_state.is_shielding() {
+            continue;
+        }
+        let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2);
+        let radius = shield.radius * shield.health.max(Fi32::ZERO) / shield.max_health;
+        canvas.filled_circle(
+            screen_position.x.to_num(),
+            screen_position.y.to_num(),
+            radius.to_num(),
+            Color::RGBA(80, 160, 255, 110),
+        )?;
+    }
+    let debug_overlay: DebugOverlay = *world.read_resource();
+    if debug_overlay.0 {
+        render_debug(canvas, (width, height), &data, &tick_rate)?;
+    }
+
+    // Draw damage percents, health and stocks above each player
+    for (physics_data, damage, stocks, health) in
+        (&data.0, &data.6, (&data.9).maybe(), (&data.10).maybe()).join()
+    {
+        let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2 - 80);
+        let mut text = format!("{}%", damage.0.to_num::<i32>());
+        if let Some(health) = health {
+            text = format!(
+                "{} HP {}",
+                health.current.max(Fi32::ZERO).to_num::<i32>(),
+                text
+            );
+        }
+        if let Some(stocks) = stocks {
+            text = format!("{} x{}", text, stocks.0);
+        }
+        draw_text(canvas, texture_creator, font, &text, screen_position.into())?;
+    }
+
+    // Draw meter bars under the percents, lit up when full
+    for (physics_data, meter) in (&data.0, &data.12).join() {
+        let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2 - 66);
+        let (x, y): (i32, i32) = (screen_position.x.to_num(), screen_position.y.to_num());
+        canvas.set_draw_color(Color::RGB(30, 30, 30));
+        canvas.fill_rect(Rect::new(x - 40, y - 3, 80, 6))?;
+        let filled = (meter.current * 80 / meter.max).to_num::<u32>();
+        if filled > 0 {
+            canvas.set_draw_color(if meter.current >= meter.max {
+                Color::RGB(255, 220, 60)
+            } else {
+                Color::RGB(200, 140, 40)
+            });
+            canvas.fill_rect(Rect::new(x - 40, y - 3, filled, 6))?;
+        }
+    }
+
+    // Draw combos being taken below each player
+    for (physics_data, player_state, combo) in (&data.0, &data.2, &data.11).join() {
+        let showing = player_state.status == PlayerStatus::Hitstun
+            || tick_rate.frames(combo.since_hit) < crate::COMBO_DISPLAY_FRAMES;
+        if combo.hits < 2 || !showing {
+            continue;
+        }
+        let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2 + 80);
+        let text = format!("{} hits {}%", combo.hits, combo.damage.to_num::<i32>());
+        draw_text(canvas, texture_creator, font, &text, screen_position.into())?;
+    }
+
+    let match_state: MatchState = *world.read_resource();
+    if match_state.over {
+        let text = match match_state.winner.and_then(|winner| data.0.get(winner)) {
+            Some(physics_data) => {
+                // Point out the winner
+                let screen_position =
+                    physics_data.position + PointFi32::new(width / 2, height / 2 - 100);
+                draw_text(
+                    canvas,
+                    texture_creator,
+                    font,
+                    "Winner",
+                    screen_position.into(),
+                )?;
+                "GAME!"
+            }
+            None => "GAME! No contest",
+        };
+        draw_text(
+            canvas,
+            texture_creator,
+            font,
+            text,
+            Point::new(width as i32 / 2, height as i32 / 4),
+        )?;
+    }
+
     // Draw the Framerate
     let fps: Framerate = *world.read_resource();
     let fps = fps.get().to_string();
@@ -74,3 +243,93 @@ pub fn render(
 
     Ok(())
 }
+
+fn draw_text(
+    canvas: &mut WindowCanvas,
+    texture_creator: &TextureCreator<WindowContext>,
+    font: &Font,
+    text: &str,
+    center: Point,
+) -> Result<(), String> {
+    let surface = font
+        .render(text)
+        .blended(Color::WHITE)
+        .map_err(|e| e.to_string())?;
+    let texture = texture_creator
+        .create_texture_from_surface(&surface)
+        .map_err(|e| e.to_string())?;
+    let (surface_width, surface_height) = surface.size();
+    canvas.copy(
+        &texture,
+        None,
+        Rect::from_center(center, surface_width, surface_height),
+    )
+}
+
+/// Draw hurtboxes, highlighting intangible and countering ones, and parry windows
+fn render_debug(
+    canvas: &mut WindowCanvas,
+    (width, height): (u32, u32),
+    data: &SystemData,
+    tick_rate: &TickRate,
+) -> Result<(), String> {
+    for (physics_data, collision_data, player_state, dodges, moveset) in (
+        &data.0,
+        &data.4,
+        (&data.2).maybe(),
+        (&data.5).maybe(),
+        (&data.8).maybe(),
+    )
+        .join()
+    {
+        let CollisionMask::Circle(center, radius) = collision_data.mask else {
+            continue;
+        };
+        let intangible = match (player_state, dodges) {
+            (Some(player_state), Some(dodges)) => dodges.is_intangible(player_state, tick_rate),
+            _ => false,
+        };
+        let countering = match (player_state, moveset) {
+            (Some(player_state), Some(moveset)) => moveset
+                .counter_multiplier(player_state, tick_rate)
+                .is_some(),
+            _ => false,
+        };
+        let color = if intangible {
+            Color::RGBA(255, 255, 255, 200)
+        } else if countering {
+            Color::RGBA(0, 255, 255, 220)
+        } else {
+            Color::RGBA(255, 220, 0, 120)
+        };
+        let screen_position =
+            physics_data.position + center + PointFi32::new(width / 2, height / 2);
+        canvas.aa_circle(
+            screen_position.x.to_num(),
+            screen_position.y.to_num(),
+            radius.to_num(),
+            color,
+        )?;
+        if intangible {
+            canvas.filled_circle(
+                screen_position.x.to_num(),
+                screen_position.y.to_num(),
+                radius.to_num(),
+                Color::RGBA(255, 255, 255, 60),
+            )?;
+        }
+    }
+    for (physics_data, player_state, shield) in (&data.0, &data.2, &data.3).join() {
+        if !player_state.is_parrying(tick_rate) {
+            continue;
+        }
+        let screen_position = physics_data.position + PointFi32::new(width / 2, height / 2);
+        canvas.aa_circle(
+            screen_position.x.to_num(),
+            screen_position.y.to_num(),
+            shield.radius.to_num(),
+            Color::RGB(255, 200, 0),
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use specs::{Entities, Join, Read, System, WriteStorage};
+
+use crate::{overflow, Fi32, PlayerState, PlayerStatus, Shield, TickRate};
+
+pub struct Shielding;
+
+impl<'a> System<'a> for Shielding {
+    type SystemData = (
+        WriteStorage<'a, PlayerState>,
+        WriteStorage<'a, Shield>,
+        Read<'a, TickRate>,
+        Entities<'a>,
+    );
+
+    fn run(&mut self, mut data: Self::SystemData) {
+        let tick_rate = *data.2;
+        for (entity, player_state, shield) in (&data.3, &mut data.0, &mut data.1).join() {
+            overflow::track(entity);
+            let regeneration = tick_rate.per_frame(shield.regeneration);
+            match player_state.status {
+                PlayerStatus::Blocking => shield.health -= tick_rate.per_frame(shield.depletion),
+                // No regeneration while the shield is being hit
+                PlayerStatus::Blockstun => (),
+                PlayerStatus::ShieldBreak => (),
+                _ => shield.health = (shield.health + regeneration).min(shield.max_health),
+            }
+
+            if player_state.is_shielding() && shield.health <= Fi32::ZERO {
+                player_state.status = PlayerStatus::ShieldBreak;
+                player_state.stun = tick_rate.ticks(shield.break_frames);
+                player_state.animation_counter = 0;
+                shield.health = shield.max_health * crate::SHIELD_BREAK_RESET;
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use specs::{Dispatcher, DispatcherBuilder};
+
+use crate::overflow::Traced;
+
+/// The systems which step the game by one frame. Rendering is left out, so the game can
+/// also be stepped headlessly.
+pub fn dispatcher<'a, 'b>() -> Dispatcher<'a, 'b> {
+    DispatcherBuilder::new()
+        .with(Traced(crate::hitlag::HitlagFreeze), "HitlagFreeze", &[])
+        .with(
+            Traced(crate::keyboard_input::Keyboard),
+            "Keyboard",
+            &["HitlagFreeze"],
+        )
+        .with(Traced(crate::meter::Metering), "Metering", &["Keyboard"])
+        .with(Traced(crate::grab::Grappling), "Grappling", &["Metering"])
+        .with(
+            Traced(crate::shield::Shielding),
+            "Shielding",
+            &["Grappling"],
+        )
+        .with(
+            Traced(crate::projectile::Projectiles),
+            "Projectiles",
+            &["Shielding"],
+        )
+        .with(Traced(crate::combat::Combat), "Combat", &["Projectiles"])
+        .with(Traced(crate::collider::Collider), "Collider", &["Combat"])
+        .with(
+            Traced(crate::collider::Repel::default()),
+            "Repel",
+            &["Collider"],
+        )
+        .with(Traced(crate::physics::Physics), "Physics", &["Repel"])
+        .with(Traced(crate::knockout::Knockout), "Knockout", &["Physics"])
+        .with(
+            Traced(crate::player_animator::PlayerAnimator),
+            "PlayerAnimator",
+            &["Knockout"],
+        )
+        .with(
+            Traced(crate::animator::Animator),
+            "Animator",
+            &["PlayerAnimator"],
+        )
+        .with(
+            Traced(crate::hitlag::HitlagRestore),
+            "HitlagRestore",
+            &["Animator"],
+        )
+        .with(
+            Traced(crate::platform::MovePlatforms),
+            "MovePlatforms",
+            &["HitlagRestore"],
+        )
+        .build()
+}
@@ -0,0 +1,261 @@
+use crate::{Direction, Fi32, PointFi32, TickRate};
+
+/// A vertical wall which blocks entities from one side
+#[derive(Debug, Clone)]
+pub struct Wall {
+    pub x: Fi32,
+    pub top: Fi32,
+    pub bottom: Fi32,
+    /// The side of the wall that entities are kept on
+    pub normal: Direction,
+}
+
+/// Players outside of these bounds are knocked out
+#[derive(Debug, Clone, Copy)]
+pub struct BlastZone {
+    pub left: Fi32,
+    pub right: Fi32,
+    pub top: Fi32,
+    pub bottom: Fi32,
+}
+
+impl BlastZone {
+    pub fn contains(&self, position: PointFi32) -> bool {
+        (self.left..=self.right).contains(&position.x)
+            && (self.top..=self.bottom).contains(&position.y)
+    }
+
+    /// Move a point inside the blast zone, at least `inset` from its edges
+    pub fn clamp(&self, position: PointFi32, inset: Fi32) -> PointFi32 {
+        PointFi32 {
+            x: position.x.clamp(self.left + inset, self.right - inset),
+            y: position.y.clamp(self.top + inset, self.bottom - inset),
+        }
+    }
+}
+
+/// A point on a platform's path, reached `frame` frames at `BASE_TICK_RATE` into the loop
+#[derive(Debug, Clone, Copy)]
+pub struct Keyframe {
+    pub frame: u32,
+    pub position: PointFi32,
+}
+
+/// A floor segment which can be landed on from above and dropped off the ends of.
+/// Segments can slope, and connect end to end to make up uneven floors.
+#[derive(Debug, Clone)]
+pub struct Platform {
+    pub position: PointFi32,
+    /// Ends of the surface relative to `position`, the left one first
+    pub left: PointFi32,
+    pub right: PointFi32,
+    /// How far the platform moved on the last frame
+    pub velocity: PointFi32,
+    /// Keyframes the platform moves between, looping after the last one. Its frame is the
+    /// length of the loop, so it usually repeats the first position. Empty for a fixed platform.
+    pub path: Vec<Keyframe>,
+}
+
+impl Platform {
+    /// A flat platform centered on `position`
+    pub fn fixed(position: PointFi32, half_width: Fi32) -> Self {
+        Platform {
+            position,
+            left: PointFi32 {
+                x: -half_width,
+                y: Fi32::ZERO,
+            },
+            right: PointFi32 {
+                x: half_width,
+                y: Fi32::ZERO,
+            },
+            velocity: PointFi32::default(),
+            path: Vec::new(),
+        }
+    }
+
+    /// A fixed floor segment between two points, which may slope
+    pub fn segment(left: PointFi32, right: PointFi32) -> Self {
+        let mut platform = Platform::fixed(left, Fi32::ZERO);
+        platform.right = right - left;
+        platform
+    }
+
+    pub fn moving(half_width: Fi32, path: Vec<Keyframe>) -> Self {
+        let mut platform = Platform::fixed(PointFi32::default(), half_width);
+        platform.path = path;
+        platform.position = platform.path_position(0).unwrap_or_default();
+        platform
+    }
+
+    /// Where the platform is `frame` frames into its path, between keyframes in a straight line
+    pub fn path_position(&self, frame: u32) -> Option<PointFi32> {
+        let first = self.path.first()?;
+        let length = self.path.last()?.frame;
+        if length == 0 {
+            return Some(first.position);
+        }
+        let frame = frame % length;
+        let next = self
+            .path
+            .iter()
+            .position(|keyframe| keyframe.frame > frame)?;
+        let Some(previous) = next.checked_sub(1).map(|previous| self.path[previous]) else {
+            return Some(first.position);
+        };
+        let next = self.path[next];
+        // Scaled by whole frames rather than a fraction, which would be too coarse in Fi32
+        let (elapsed, span) = (
+            (frame - previous.frame) as i32,
+            (next.frame - previous.frame) as i32,
+        );
+        let offset = next.position - previous.position;
+        Some(
+            previous.position
+                + PointFi32 {
+                    x: offset.x * elapsed / span,
+                    y: offset.y * elapsed / span,
+                },
+        )
+    }
+
+    /// Where the platform is `tick` ticks into its path, moving smoothly between frames when
+    /// they last several ticks
+    pub fn path_position_at(&self, tick: u32, tick_rate: &TickRate) -> Option<PointFi32> {
+        let frame = tick_rate.frames(tick);
+        let position = self.path_position(frame)?;
+        let start = tick_rate.ticks(frame);
+        let span = tick_rate.ticks(frame.wrapping_add(1)).saturating_sub(start) as i32;
+        let elapsed = tick.saturating_sub(start) as i32;
+        if elapsed == 0 || span <= elapsed {
+            return Some(position);
+        }
+        let offset = self.path_position(frame.wrapping_add(1))? - position;
+        Some(
+            position
+                + PointFi32 {
+                    x: offset.x * elapsed / span,
+                    y: offset.y * elapsed / span,
+                },
+        )
+    }
+
+    /// Unit vector along the surface, from left to right
+    pub fn direction(&self) -> PointFi32 {
+        (self.right - self.left).normalize()
+    }
+
+    /// Whether the surface is above or below `x`
+    pub fn spans(&self, x: Fi32) -> bool {
+        (self.position.x + self.left.x..=self.position.x + self.right.x).contains(&x)
+    }
+
+    /// Height of the surface at `x`, continuing flat past its ends
+    pub fn height_at(&self, x: Fi32) -> Fi32 {
+        let (left, right) = (self.position + self.left, self.position + self.right);
+        if x <= left.x || right.x <= left.x {
+            left.y
+        } else if x >= right.x {
+            right.y
+        } else {
+            left.y + (right.y - left.y) * (x - left.x) / (right.x - left.x)
+        }
+    }
+
+    /// How far a point is above the surface along its normal, scaled by the surface's length.
+    /// Negative below the surface.
+    fn clearance(&self, position: PointFi32, platform_position: PointFi32) -> i64 {
+        let along = self.right - self.left;
+        let normal = PointFi32 {
+            x: along.y,
+            y: -along.x,
+        };
+        (position - (platform_position + self.left)).dot_wide(normal)
+    }
+
+    /// Whether a point above the platform comes down onto it moving by `motion`, assuming
+    /// the platform keeps moving at its current velocity
+    pub fn lands(&self, position: PointFi32, motion: PointFi32) -> bool {
+        let next = self.position + self.velocity;
+        let end = position + motion;
+        self.clearance(position, self.position) >= 0
+            && self.clearance(end, next) <= 0
+            && (next.x + self.left.x..=next.x + self.right.x).contains(&end.x)
+    }
+}
+
+/// Stage geometry
+#[derive(Debug, Clone, Default)]
+pub struct Stage {
+    pub walls: Vec<Wall>,
+    pub platforms: Vec<Platform>,
+    /// Ticks simulated, which moving platforms follow their paths by
+    pub frame: u32,
+    /// Without a blast zone players can only be knocked out by losing all their health
+    pub blast_zone: Option<BlastZone>,
+    /// Where the respawn platform appears
+    pub respawn: PointFi32,
+}
+
+impl Stage {
+    /// The highest platform landed on by a point moving by `motion`
+    pub fn landing(&self, position: PointFi32, motion: PointFi32) -> Option<usize> {
+        self.platforms
+            .iter()
+            .enumerate()
+            .filter(|(_, platform)| platform.lands(position, motion))
+            .min_by_key(|(_, platform)| platform.height_at(position.x + motion.x))
+            .map(|(i, _)| i)
+    }
+
+    /// The floor segment continuing on from `from` under a grounded point which has walked
+    /// by `movement` past its end, if it is near enough to step onto
+    pub fn next_floor(
+        &self,
+        from: usize,
+        position: PointFi32,
+        movement: PointFi32,
+    ) -> Option<usize> {
+        let reach = movement.x.abs() * crate::MAX_WALK_SLOPE + Fi32::ONE;
+        self.platforms
+            .iter()
+            .enumerate()
+            .filter(|&(i, platform)| {
+                i != from
+                    && platform.spans(position.x)
+                    && (platform.height_at(position.x) - position.y).abs() <= reach
+            })
+            .min_by_key(|(_, platform)| (platform.height_at(position.x) - position.y).abs())
+            .map(|(i, _)| i)
+    }
+
+    /// Push a body of the given radius out of any walls, cancelling speed into them.
+    /// Returns the side of the body on which a wall is touching, if any.
+    pub fn resolve_walls(
+        &self,
+        position: &mut PointFi32,
+        speed: &mut PointFi32,
+        radius: Fi32,
+    ) -> Option<Direction> {
+        let mut contact = None;
+        for wall in &self.walls {
+            if position.y < wall.top || position.y > wall.bottom {
+                continue;
+            }
+            match wall.normal {
+                Direction::Right if position.x - radius <= wall.x => {
+                    position.x = wall.x + radius;
+                    speed.x = speed.x.max(Fi32::ZERO);
+                    contact = Some(Direction::Left);
+                }
+                Direction::Left if position.x + radius >= wall.x => {
+                    position.x = wall.x - radius;
+                    speed.x = speed.x.min(Fi32::ZERO);
+                    contact = Some(Direction::Right);
+                }
+                _ => (),
+            }
+        }
+        contact
+    }
+}
@@ -0,0 +1,404 @@
+mod common;
+
+use proptest::prelude::*;
+use specs::shrev::{EventChannel, ReaderId};
+use specs::{Builder, Dispatcher, Entity, World, WorldExt};
+
+use sm::characters::Character;
+use sm::collider::sweep_and_prune;
+use sm::{
+    CollisionData, CollisionEvent, CollisionLayer, CollisionMask, Damage, Direction, Fi32, Input,
+    Interaction, MovementStats, PhysicsData, Platform, PointFi32, Ruleset, Stage, TickRate,
+};
+
+type Bounds = Option<(PointFi32, PointFi32)>;
+
+fn rect(min: (i32, i32), max: (i32, i32)) -> Bounds {
+    Some((PointFi32::new(min.0, min.1), PointFi32::new(max.0, max.1)))
+}
+
+/// Every overlapping pair, checked one by one
+fn pairwise(bounds: &[Bounds]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for i in 0..bounds.len() {
+        for j in i + 1..bounds.len() {
+            let (Some((a_min, a_max)), Some((b_min, b_max))) = (bounds[i], bounds[j]) else {
+                continue;
+            };
+            if a_min.x <= b_max.x && b_min.x <= a_max.x && a_min.y <= b_max.y && b_min.y <= a_max.y
+            {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+fn bounds() -> impl Strategy<Value = Bounds> {
+    prop_oneof![
+        1 => Just(None),
+        8 => (-50..50, -50..50, 0..30, 0..30).prop_map(|(x, y, width, height)| {
+            rect((x, y), (x + width, y + height))
+        }),
+    ]
+}
+
+#[test]
+fn ties_on_min_x() {
+    let bounds = [
+        rect((0, 20), (10, 30)),
+        rect((0, 0), (10, 10)),
+        rect((0, 5), (10, 25)),
+    ];
+    assert_eq!(sweep_and_prune(&bounds), vec![(0, 2), (1, 2)]);
+}
+
+#[test]
+fn touching_edges_overlap() {
+    let bounds = [
+        rect((0, 0), (10, 10)),
+        rect((10, 0), (20, 10)),
+        rect((0, 10), (10, 20)),
+        rect((21, 0), (30, 10)),
+    ];
+    assert_eq!(sweep_and_prune(&bounds), vec![(0, 1), (0, 2), (1, 2)]);
+}
+
+#[test]
+fn skips_box_masks() {
+    let unbounded = CollisionMask::Box.bounds(PointFi32::new(5, 5));
+    assert_eq!(unbounded, None);
+    let bounds = [
+        unbounded,
+        rect((0, 0), (10, 10)),
+        unbounded,
+        rect((5, 5), (15, 15)),
+    ];
+    assert_eq!(sweep_and_prune(&bounds), vec![(1, 3)]);
+    assert_eq!(sweep_and_prune(&[unbounded, unbounded]), vec![]);
+}
+
+proptest! {
+    #[test]
+    fn matches_pairwise(bounds in prop::collection::vec(bounds(), 0..40)) {
+        prop_assert_eq!(sweep_and_prune(&bounds), pairwise(&bounds));
+    }
+
+    #[test]
+    fn independent_of_input_order(
+        (bounds, order) in prop::collection::vec(bounds(), 0..40)
+            .prop_flat_map(|bounds| {
+                let order = Just((0..bounds.len()).collect::<Vec<_>>()).prop_shuffle();
+                (Just(bounds), order)
+            })
+    ) {
+        let shuffled = order.iter().map(|&i| bounds[i]).collect::<Vec<_>>();
+        let mut pairs = sweep_and_prune(&shuffled)
+            .into_iter()
+            .map(|(i, j)| (order[i].min(order[j]), order[i].max(order[j])))
+            .collect::<Vec<_>>();
+        pairs.sort_unstable();
+        prop_assert_eq!(pairs, sweep_and_prune(&bounds));
+    }
+
+    #[test]
+    fn sorted_and_ordered(bounds in prop::collection::vec(bounds(), 0..40)) {
+        let pairs = sweep_and_prune(&bounds);
+        prop_assert!(pairs.iter().all(|(i, j)| i < j));
+        prop_assert!(pairs.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+}
+
+#[test]
+fn fixed_point_bounds() {
+    let half = Fi32::from_num(0.5);
+    let a = Some((PointFi32::new(0, 0), PointFi32 { x: half, y: half }));
+    let b = Some((PointFi32 { x: half, y: half }, PointFi32::new(1, 1)));
+    let c = Some((
+        PointFi32 {
+            x: half + Fi32::DELTA,
+            y: Fi32::ZERO,
+        },
+        PointFi32::new(1, 1),
+    ));
+    assert_eq!(sweep_and_prune(&[a, b, c]), vec![(0, 1), (1, 2)]);
+}
+
+/// An empty stage, with a reader for its collision events
+fn setup() -> (
+    World,
+    Dispatcher<'static, 'static>,
+    ReaderId<CollisionEvent>,
+) {
+    let (world, dispatcher) = common::world(Stage::default(), TickRate::default());
+    let reader = world
+        .fetch_mut::<EventChannel<CollisionEvent>>()
+        .register_reader();
+    (world, dispatcher, reader)
+}
+
+fn physics_data(position: PointFi32) -> PhysicsData {
+    PhysicsData {
+        position,
+        speed: PointFi32::default(),
+        acceleration: PointFi32::default(),
+        wall_contact: None,
+        ground: None,
+    }
+}
+
+/// A still circle on the given layer
+fn body(world: &mut World, position: PointFi32, radius: i32, layer: CollisionLayer) -> Entity {
+    world
+        .create_entity()
+        .with(physics_data(position))
+        .with(CollisionData {
+            mask: CollisionMask::Circle(PointFi32::default(), Fi32::from_num(radius)),
+            layer,
+            repel_speed: Fi32::from_num(4),
+        })
+        .build()
+}
+
+fn step(
+    world: &mut World,
+    dispatcher: &mut Dispatcher,
+    reader: &mut ReaderId<CollisionEvent>,
+) -> Vec<CollisionEvent> {
+    common::step(world, dispatcher, 1);
+    world
+        .read_resource::<EventChannel<CollisionEvent>>()
+        .read(reader)
+        .copied()
+        .collect()
+}
+
+#[test]
+fn reports_contact_normal_and_depth() {
+    let (mut world, mut dispatcher, mut reader) = setup();
+    // Physics-only entities are never paired with another entity's collision data
+    let loose = world
+        .create_entity()
+        .with(physics_data(PointFi32::new(1000, 0)))
+        .build();
+    let a = body(
+        &mut world,
+        PointFi32::new(0, 0),
+        30,
+        CollisionLayer::Hurtbox,
+    );
+    let b = body(
+        &mut world,
+        PointFi32::new(0, 40),
+        30,
+        CollisionLayer::Hitbox,
+    );
+    let far = body(
+        &mut world,
+        PointFi32::new(500, 0),
+        30,
+        CollisionLayer::Hitbox,
+    );
+
+    let events = step(&mut world, &mut dispatcher, &mut reader);
+    assert_eq!(events.len(), 1);
+    let event = events[0];
+    assert_eq!((event.a, event.b), (a, b));
+    assert!(![event.a, event.b].contains(&loose) && ![event.a, event.b].contains(&far));
+    assert_eq!(event.interaction, Interaction::Overlap);
+    assert_eq!(event.time_of_impact, Fi32::ZERO);
+    assert_eq!(event.normal, PointFi32::new(0, 1));
+    assert_eq!(event.depth, Fi32::from_num(20));
+}
+
+/// Fighters spawned in order on a floor, split into two teams by the ruleset
+fn teams(world: &mut World, positions: &[PointFi32]) -> Vec<Entity> {
+    let ruleset = Ruleset {
+        teams: 2,
+        ..Ruleset::default()
+    };
+    world.insert(ruleset);
+    world.insert(Stage {
+        platforms: vec![Platform::fixed(PointFi32::new(0, 0), Fi32::from_num(600))],
+        ..Stage::default()
+    });
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, &position)| {
+            let mut builder =
+                Character::Fighter.build(world.create_entity(), position, Direction::Right);
+            if let Some(team) = ruleset.team(i) {
+                builder = builder.with(team);
+            }
+            if i == 0 {
+                builder = builder.with(sm::Player1);
+            }
+            builder.build()
+        })
+        .collect()
+}
+
+#[test]
+fn teammates_pass_through_each_other() {
+    let (mut world, mut dispatcher, mut reader) = setup();
+    let players = teams(
+        &mut world,
+        &[
+            PointFi32::new(0, 0),
+            PointFi32::new(20, 0),
+            PointFi32::new(0, -20),
+            PointFi32::new(20, -20),
+        ],
+    );
+    let mut pairs = step(&mut world, &mut dispatcher, &mut reader)
+        .into_iter()
+        .filter(|event| event.interaction == Interaction::Push)
+        .map(|event| {
+            let a = players
+                .iter()
+                .position(|&player| player == event.a)
+                .unwrap();
+            let b = players
+                .iter()
+                .position(|&player| player == event.b)
+                .unwrap();
+            (a.min(b), a.max(b))
+        })
+        .collect::<Vec<_>>();
+    pairs.sort_unstable();
+    // Players 0 and 2 against 1 and 3
+    assert_eq!(pairs, vec![(0, 1), (0, 3), (1, 2), (2, 3)]);
+}
+
+#[test]
+fn teammates_cannot_hit_each_other() {
+    let (mut world, mut dispatcher, mut reader) = setup();
+    let players = teams(
+        &mut world,
+        &[
+            PointFi32::new(0, 0),
+            PointFi32::new(50, 0),
+            PointFi32::new(50, 0),
+        ],
+    );
+    for _ in 0..5 {
+        step(&mut world, &mut dispatcher, &mut reader);
+    }
+    world
+        .write_resource::<sm::InputBuffer>()
+        .push([Input::Attack].into());
+    step(&mut world, &mut dispatcher, &mut reader);
+    world.write_resource::<sm::InputBuffer>().push([].into());
+    for _ in 0..30 {
+        step(&mut world, &mut dispatcher, &mut reader);
+    }
+    let damage = |player: Entity| world.read_storage::<Damage>().get(player).unwrap().0;
+    assert!(damage(players[1]) > Fi32::ZERO);
+    assert_eq!(damage(players[2]), Fi32::ZERO);
+}
+
+/// A still pushbox of radius 30 with the given weight and repel speed
+fn pushbox(world: &mut World, position: PointFi32, weight: i32, repel_speed: i32) -> Entity {
+    world
+        .create_entity()
+        .with(physics_data(position))
+        .with(CollisionData {
+            mask: CollisionMask::Circle(PointFi32::default(), Fi32::from_num(30)),
+            layer: CollisionLayer::Pushbox,
+            repel_speed: Fi32::from_num(repel_speed),
+        })
+        .with(MovementStats {
+            max_speed: Fi32::ZERO,
+            acceleration: Fi32::ZERO,
+            friction: Fi32::ZERO,
+            gravity: Fi32::ZERO,
+            jump_power: Fi32::ZERO,
+            superjump_power: Fi32::ZERO,
+            air_acceleration: Fi32::ZERO,
+            air_max_speed: Fi32::ZERO,
+            landing_lag: 0,
+            weight: Fi32::from_num(weight),
+        })
+        .build()
+}
+
+fn position(world: &World, entity: Entity) -> PointFi32 {
+    world
+        .read_storage::<PhysicsData>()
+        .get(entity)
+        .unwrap()
+        .position
+}
+
+/// Step until two pushboxes stop overlapping, returning how far each moved
+fn separate(
+    world: &mut World,
+    dispatcher: &mut Dispatcher,
+    reader: &mut ReaderId<CollisionEvent>,
+    (a, b): (Entity, Entity),
+) -> (Fi32, Fi32) {
+    let (a_start, b_start) = (position(world, a), position(world, b));
+    for _ in 0..200 {
+        let events = step(world, dispatcher, reader);
+        if events.iter().all(|event| event.depth <= Fi32::ZERO) {
+            let (a_end, b_end) = (position(world, a), position(world, b));
+            assert_eq!(a_end.y, b_end.y);
+            return ((a_end - a_start).length(), (b_end - b_start).length());
+        }
+    }
+    panic!("never separated");
+}
+
+#[test]
+fn separates_coincident_pushboxes() {
+    let (mut world, mut dispatcher, mut reader) = setup();
+    let heavy = pushbox(&mut world, PointFi32::new(10, 10), 150, 3);
+    let light = pushbox(&mut world, PointFi32::new(10, 10), 50, 3);
+    let (heavy_moved, light_moved) =
+        separate(&mut world, &mut dispatcher, &mut reader, (heavy, light));
+    assert!(light_moved > heavy_moved);
+    // Split along x by entity order
+    assert!(position(&world, heavy).x < position(&world, light).x);
+}
+
+#[test]
+fn separates_without_repel_speed() {
+    let (mut world, mut dispatcher, mut reader) = setup();
+    let light = pushbox(&mut world, PointFi32::new(0, 0), 60, 0);
+    let heavy = pushbox(&mut world, PointFi32::new(20, 0), 120, 0);
+    let (light_moved, heavy_moved) =
+        separate(&mut world, &mut dispatcher, &mut reader, (light, heavy));
+    assert!(light_moved > heavy_moved);
+    assert!(position(&world, light).x < Fi32::ZERO);
+    assert!(position(&world, heavy).x > Fi32::from_num(20));
+}
+
+#[test]
+fn stops_at_the_edge_of_the_range() {
+    let (mut world, mut dispatcher, mut reader) = setup();
+    let edge = Fi32::MAX - Fi32::from_num(10);
+    let body = world
+        .create_entity()
+        .with(PhysicsData {
+            speed: PointFi32::new(100, 0),
+            ..physics_data(PointFi32 {
+                x: edge,
+                y: Fi32::ZERO,
+            })
+        })
+        .with(CollisionData {
+            mask: CollisionMask::Circle(PointFi32::default(), Fi32::from_num(8)),
+            layer: CollisionLayer::Pushbox,
+            repel_speed: Fi32::from_num(4),
+        })
+        .build();
+    step(&mut world, &mut dispatcher, &mut reader);
+    let physics_data = world
+        .read_storage::<PhysicsData>()
+        .get(body)
+        .unwrap()
+        .clone();
+    assert!(physics_data.position.x >= edge);
+    assert_eq!(physics_data.speed, PointFi32::default());
+}
@@ -0,0 +1,83 @@
+//! Worlds stepped the same way as the game, shared by the simulation tests
+#![allow(dead_code)]
+
+use std::collections::HashSet;
+
+use specs::{Builder, Dispatcher, Entity, World, WorldExt};
+
+use sm::characters::Character;
+use sm::{Direction, Fi32, Input, PhysicsData, Platform, PlayerState, PointFi32, Stage, TickRate};
+
+/// A world on the given stage, with a dispatcher running the game's systems at the given tick rate
+pub fn world(stage: Stage, tick_rate: TickRate) -> (World, Dispatcher<'static, 'static>) {
+    let mut world = World::new();
+    world.insert(sm::InputBuffer::new());
+    world.insert(tick_rate);
+    world.insert(stage);
+    let mut dispatcher = sm::simulation::dispatcher();
+    dispatcher.setup(&mut world);
+    (world, dispatcher)
+}
+
+/// A wide flat floor at the origin
+pub fn floor() -> Vec<Platform> {
+    vec![Platform::fixed(PointFi32::new(0, 0), Fi32::from_num(600))]
+}
+
+/// A world with the given platforms and one idle fighter
+pub fn setup(
+    platforms: Vec<Platform>,
+    tick_rate: TickRate,
+    position: PointFi32,
+) -> (World, Dispatcher<'static, 'static>, Entity) {
+    let (mut world, dispatcher) = world(
+        Stage {
+            platforms,
+            ..Stage::default()
+        },
+        tick_rate,
+    );
+    let fighter = Character::Fighter
+        .build(world.create_entity(), position, Direction::Right)
+        .build();
+    (world, dispatcher, fighter)
+}
+
+/// Drive the entity with the input buffer, as the keyboard does
+pub fn control(world: &mut World, entity: Entity) {
+    world
+        .write_storage::<sm::Player1>()
+        .insert(entity, sm::Player1)
+        .unwrap();
+}
+
+pub fn step(world: &mut World, dispatcher: &mut Dispatcher, ticks: u32) {
+    for _ in 0..ticks {
+        dispatcher.dispatch(world);
+        world.maintain();
+    }
+}
+
+/// Step a tick holding the given inputs, as the game loop does
+pub fn hold(world: &mut World, dispatcher: &mut Dispatcher, inputs: &[Input]) {
+    world
+        .write_resource::<sm::InputBuffer>()
+        .push(inputs.iter().copied().collect::<HashSet<_>>());
+    step(world, dispatcher, 1);
+}
+
+pub fn physics(world: &World, entity: Entity) -> PhysicsData {
+    world
+        .read_storage::<PhysicsData>()
+        .get(entity)
+        .unwrap()
+        .clone()
+}
+
+pub fn state(world: &World, entity: Entity) -> PlayerState {
+    world
+        .read_storage::<PlayerState>()
+        .get(entity)
+        .unwrap()
+        .clone()
+}
@@ -0,0 +1,242 @@
+use std::f64::consts::TAU;
+
+use proptest::prelude::*;
+use sm::{Angle, Fi32, PointFi32};
+
+/// 2^-20, the relative error allowed on top of rounding to the nearest Fi32
+const RELATIVE: f64 = 1.0 / 1048576.0;
+
+fn radians(angle: Angle) -> f64 {
+    f64::from(angle.0) / 65536.0 * TAU
+}
+
+/// Smallest difference between two angles in radians, wrapping around
+fn angle_error(a: f64, b: f64) -> f64 {
+    let difference = (a - b).rem_euclid(TAU);
+    difference.min(TAU - difference)
+}
+
+fn fi32() -> impl Strategy<Value = Fi32> {
+    any::<i32>().prop_map(Fi32::from_bits)
+}
+
+/// Points small enough that differences and products stay in range
+fn point(limit: i32) -> impl Strategy<Value = PointFi32> {
+    (-limit..=limit, -limit..=limit).prop_map(|(x, y)| PointFi32 {
+        x: Fi32::from_bits(x),
+        y: Fi32::from_bits(y),
+    })
+}
+
+/// Points anywhere in the `Fi32` range, leaning towards the extremes where overflow happens
+fn any_point() -> impl Strategy<Value = PointFi32> {
+    let coordinate = prop_oneof![
+        fi32(),
+        Just(Fi32::MAX),
+        Just(Fi32::MIN),
+        (-4..=4).prop_map(|offset: i32| Fi32::from_bits(i32::MAX - 4 + offset)),
+    ];
+    (coordinate.clone(), coordinate).prop_map(|(x, y)| PointFi32 { x, y })
+}
+
+/// Exact value of a wide op in i128, saturated to i64 as the op should be
+fn saturate(exact: i128) -> i64 {
+    exact.clamp(i64::MIN.into(), i64::MAX.into()) as i64
+}
+
+fn bits(point: PointFi32) -> (i128, i128) {
+    (point.x.to_bits().into(), point.y.to_bits().into())
+}
+
+fn length(point: PointFi32) -> f64 {
+    point.x.to_num::<f64>().hypot(point.y.to_num())
+}
+
+proptest! {
+    #[test]
+    fn sin_and_cos(angle in any::<u16>().prop_map(Angle)) {
+        let bound = 1.0 / 256.0 + RELATIVE;
+        prop_assert!((angle.sin().to_num::<f64>() - radians(angle).sin()).abs() <= bound);
+        prop_assert!((angle.cos().to_num::<f64>() - radians(angle).cos()).abs() <= bound);
+    }
+
+    #[test]
+    fn atan2(x in fi32(), y in fi32()) {
+        prop_assume!(x != Fi32::ZERO || y != Fi32::ZERO);
+        let exact = y.to_num::<f64>().atan2(x.to_num());
+        prop_assert!(angle_error(radians(Angle::atan2(y, x)), exact) <= TAU / 65536.0);
+    }
+
+    #[test]
+    fn degrees_round_trip(angle in any::<u16>().prop_map(Angle)) {
+        // Degrees are coarser than angles, so the round trip can be one step off
+        let turned = (Angle::from_degrees(angle.degrees()) - angle).0 as i16;
+        prop_assert!(turned.abs() <= 1);
+        let exact = f64::from(angle.0 as i16) / 65536.0 * 360.0;
+        prop_assert!((angle.degrees().to_num::<f64>() - exact).abs() <= 1.0 / 256.0);
+    }
+
+    #[test]
+    fn radians_match(angle in any::<u16>().prop_map(Angle)) {
+        let exact = f64::from(angle.0 as i16) / 65536.0 * TAU;
+        prop_assert!((angle.radians().to_num::<f64>() - exact).abs() <= 1.0 / 256.0);
+        prop_assert!(angle_error(radians(Angle::from_radians(angle.radians())), exact) <= 1.0 / 128.0);
+    }
+
+    #[test]
+    fn angles_wrap(a in any::<u16>().prop_map(Angle), b in any::<u16>().prop_map(Angle)) {
+        prop_assert_eq!(a + b - b, a);
+        prop_assert_eq!(a + -a, Angle::ZERO);
+    }
+
+    #[test]
+    fn point_length(point in point(1 << 30)) {
+        let exact = length(point);
+        prop_assert!((point.length().to_num::<f64>() - exact).abs() <= 1.0 / 256.0 + exact * RELATIVE);
+    }
+
+    #[test]
+    fn rotate(point in point(1 << 30), angle in any::<u16>().prop_map(Angle)) {
+        let (sin, cos) = radians(angle).sin_cos();
+        let (x, y) = (point.x.to_num::<f64>(), point.y.to_num::<f64>());
+        let rotated = point.rotate(angle);
+        let bound = 1.0 / 256.0 + length(point) * RELATIVE;
+        prop_assert!((rotated.x.to_num::<f64>() - (x * cos - y * sin)).abs() <= bound);
+        prop_assert!((rotated.y.to_num::<f64>() - (x * sin + y * cos)).abs() <= bound);
+    }
+
+    #[test]
+    fn rotate_matches_angle(point in point(1 << 20), angle in any::<u16>().prop_map(Angle)) {
+        prop_assume!(length(point) > 4.0);
+        let turned = point.rotate(angle).angle() - point.angle();
+        // Rounding moves the end of the vector by about a step, and each atan2 rounds to an angle step
+        let bound = 1.0 / length(point) + 2.0 * TAU / 65536.0;
+        prop_assert!(angle_error(radians(turned), radians(angle)) <= bound);
+    }
+
+    #[test]
+    fn lerp(a in point(1 << 28), b in point(1 << 28), t in (0..=128).prop_map(Fi32::from_bits)) {
+        prop_assert_eq!(a.lerp(b, Fi32::ZERO), a);
+        prop_assert_eq!(a.lerp(b, Fi32::ONE), b);
+        let t_f64 = t.to_num::<f64>();
+        let lerped = a.lerp(b, t);
+        let exact_x = a.x.to_num::<f64>() + (b.x.to_num::<f64>() - a.x.to_num::<f64>()) * t_f64;
+        let exact_y = a.y.to_num::<f64>() + (b.y.to_num::<f64>() - a.y.to_num::<f64>()) * t_f64;
+        prop_assert!((lerped.x.to_num::<f64>() - exact_x).abs() <= 1.0 / 128.0);
+        prop_assert!((lerped.y.to_num::<f64>() - exact_y).abs() <= 1.0 / 128.0);
+    }
+
+    #[test]
+    fn clamp_length(point in point(1 << 28), max in (0..1 << 28).prop_map(Fi32::from_bits)) {
+        let clamped = point.clamp_length(max);
+        let exact = length(point);
+        let slack = 1.0 / 64.0 + exact * RELATIVE * 4.0;
+        if exact <= max.to_num::<f64>() {
+            prop_assert_eq!(clamped, point);
+        } else {
+            let scale = max.to_num::<f64>() / exact;
+            prop_assert!(length(clamped) <= max.to_num::<f64>() + slack);
+            prop_assert!((clamped.x.to_num::<f64>() - point.x.to_num::<f64>() * scale).abs() <= slack);
+            prop_assert!((clamped.y.to_num::<f64>() - point.y.to_num::<f64>() * scale).abs() <= slack);
+        }
+    }
+
+    #[test]
+    fn dot_wide(a in any_point(), b in any_point()) {
+        let ((ax, ay), (bx, by)) = (bits(a), bits(b));
+        prop_assert_eq!(a.dot_wide(b), saturate(ax * bx + ay * by));
+        prop_assert_eq!(a.length_squared_wide(), saturate(ax * ax + ay * ay));
+    }
+
+    #[test]
+    fn dot_wide_matches_dot(a in point(1 << 18), b in point(1 << 18)) {
+        // Small enough that the Fi32 dot product is exact
+        let ((ax, ay), (bx, by)) = (bits(a), bits(b));
+        let exact = (ax * bx + ay * by) as f64 / 16384.0;
+        prop_assert_eq!(a.dot_wide(b) as f64 / 16384.0, exact);
+        prop_assert!((a.dot(b).to_num::<f64>() - exact).abs() <= 2.0 / 128.0);
+    }
+
+    #[test]
+    fn distance_squared_wide(a in any_point(), b in any_point()) {
+        let ((ax, ay), (bx, by)) = (bits(a), bits(b));
+        let (dx, dy) = (ax - bx, ay - by);
+        prop_assert_eq!(a.distance_squared_wide(b), saturate(dx * dx + dy * dy));
+        prop_assert_eq!(a.distance_squared_wide(b), b.distance_squared_wide(a));
+    }
+
+    #[test]
+    fn checked_ops(a in any_point(), b in any_point(), small in point(1 << 8)) {
+        let ((ax, ay), (bx, by)) = (bits(a), bits(b));
+        let (sx, sy) = bits(small);
+        let in_range = |exact: i128| i32::try_from(exact).is_ok();
+        match a.checked_add(b) {
+            Some(sum) => {
+                prop_assert!(in_range(ax + bx) && in_range(ay + by));
+                prop_assert_eq!(bits(sum), (ax + bx, ay + by));
+            }
+            None => prop_assert!(!in_range(ax + bx) || !in_range(ay + by)),
+        }
+        match a.checked_sub(b) {
+            Some(difference) => {
+                prop_assert!(in_range(ax - bx) && in_range(ay - by));
+                prop_assert_eq!(bits(difference), (ax - bx, ay - by));
+            }
+            None => prop_assert!(!in_range(ax - bx) || !in_range(ay - by)),
+        }
+        // Products are rounded down to the nearest Fi32
+        let (px, py) = ((ax * sx) >> 7, (ay * sx) >> 7);
+        match a.checked_mul(small.x) {
+            Some(product) => {
+                prop_assert!(in_range(px) && in_range(py));
+                prop_assert_eq!(bits(product), (px, py));
+            }
+            None => prop_assert!(!in_range(px) || !in_range(py)),
+        }
+        let (px, py) = ((ax * sx) >> 7, (ay * sy) >> 7);
+        match a.checked_dot(small) {
+            Some(dot) => {
+                prop_assert!(in_range(px) && in_range(py) && in_range(px + py));
+                prop_assert_eq!(i128::from(dot.to_bits()), px + py);
+            }
+            None => prop_assert!(!in_range(px) || !in_range(py) || !in_range(px + py)),
+        }
+    }
+}
+
+#[test]
+fn wide_ops_saturate() {
+    let min = PointFi32 {
+        x: Fi32::MIN,
+        y: Fi32::MIN,
+    };
+    let max = PointFi32 {
+        x: Fi32::MAX,
+        y: Fi32::MAX,
+    };
+    assert_eq!(min.length_squared_wide(), i64::MAX);
+    assert_eq!(min.dot_wide(max), i64::MIN + (1 << 32));
+    assert_eq!(min.distance_squared_wide(max), i64::MAX);
+    let delta = PointFi32 {
+        x: Fi32::DELTA,
+        y: Fi32::DELTA,
+    };
+    assert_eq!(max.checked_add(PointFi32::new(0, 0)), Some(max));
+    assert_eq!(max.checked_add(delta), None);
+    assert_eq!(min.checked_add(-delta), None);
+    assert_eq!(min.checked_add(delta), Some(min + delta));
+    assert_eq!(max.saturating_add(delta), max);
+    assert_eq!(min.saturating_sub(delta), min);
+    assert_eq!(min.saturating_add(delta), min + delta);
+}
+
+#[test]
+fn quarter_turns() {
+    assert_eq!(Angle::ZERO.unit(), PointFi32::new(1, 0));
+    assert_eq!(Angle::QUARTER.unit(), PointFi32::new(0, 1));
+    assert_eq!(Angle::HALF.unit(), PointFi32::new(-1, 0));
+    assert_eq!((-Angle::QUARTER).unit(), PointFi32::new(0, -1));
+    assert_eq!(Angle::from_degrees(90), Angle::QUARTER);
+    assert_eq!(PointFi32::new(0, -5).angle(), -Angle::QUARTER);
+    assert_eq!(PointFi32::new(3, 4).length(), Fi32::from_num(5));
+}
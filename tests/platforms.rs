@@ -0,0 +1,231 @@
+mod common;
+
+use specs::{Dispatcher, Entity, World, WorldExt};
+
+use common::{physics, setup, step};
+use sm::{Fi32, Keyframe, PhysicsData, Platform, PointFi32, Stage, TickRate};
+
+fn platform(world: &World, index: usize) -> Platform {
+    world.read_resource::<Stage>().platforms[index].clone()
+}
+
+/// Back and forth along a line over `frames` frames each way
+fn shuttle(half_width: i32, from: PointFi32, to: PointFi32, frames: u32) -> Platform {
+    Platform::moving(
+        Fi32::from_num(half_width),
+        vec![
+            Keyframe {
+                frame: 0,
+                position: from,
+            },
+            Keyframe {
+                frame: frames,
+                position: to,
+            },
+            Keyframe {
+                frame: frames * 2,
+                position: from,
+            },
+        ],
+    )
+}
+
+#[test]
+fn follows_keyframes() {
+    let platform = shuttle(50, PointFi32::new(0, 0), PointFi32::new(100, -40), 20);
+    assert_eq!(platform.path_position(0), Some(PointFi32::new(0, 0)));
+    assert_eq!(platform.path_position(10), Some(PointFi32::new(50, -20)));
+    assert_eq!(platform.path_position(20), Some(PointFi32::new(100, -40)));
+    assert_eq!(platform.path_position(30), Some(PointFi32::new(50, -20)));
+    assert_eq!(platform.path_position(40), Some(PointFi32::new(0, 0)));
+    assert_eq!(platform.path_position(50), Some(PointFi32::new(50, -20)));
+    assert_eq!(
+        Platform::fixed(PointFi32::new(3, 4), Fi32::ONE).path_position(7),
+        None
+    );
+}
+
+#[test]
+fn moves_between_frames_at_higher_tick_rates() {
+    let platform = shuttle(50, PointFi32::new(0, 0), PointFi32::new(100, -40), 20);
+    let tick_rate = TickRate {
+        hz: 120,
+        ..TickRate::default()
+    };
+    assert_eq!(
+        platform.path_position_at(20, &tick_rate),
+        platform.path_position(10)
+    );
+    assert_eq!(
+        platform.path_position_at(21, &tick_rate),
+        Some(PointFi32::new(52.5, -21))
+    );
+    assert_eq!(
+        platform.path_position_at(10, &TickRate::default()),
+        platform.path_position(10)
+    );
+}
+
+#[test]
+fn lands_on_the_ground() {
+    let ground = Platform::fixed(PointFi32::new(0, 0), Fi32::from_num(600));
+    let (mut world, mut dispatcher, fighter) =
+        setup(vec![ground], TickRate::default(), PointFi32::new(0, -100));
+    step(&mut world, &mut dispatcher, 60);
+    let physics_data = physics(&world, fighter);
+    assert_eq!(physics_data.ground, Some(0));
+    assert_eq!(physics_data.position, PointFi32::new(0, 0));
+    assert_eq!(physics_data.speed, PointFi32::new(0, 0));
+}
+
+#[test]
+fn carried_by_moving_platform() {
+    let moving = shuttle(
+        80,
+        PointFi32::new(-100, -100),
+        PointFi32::new(100, -100),
+        100,
+    );
+    let (mut world, mut dispatcher, fighter) = setup(
+        vec![moving],
+        TickRate::default(),
+        PointFi32::new(-100, -160),
+    );
+    step(&mut world, &mut dispatcher, 30);
+    let physics_data = physics(&world, fighter);
+    assert_eq!(physics_data.ground, Some(0));
+    let offset = physics_data.position - platform(&world, 0).position;
+    assert_eq!(offset.y, Fi32::ZERO);
+
+    // Stays at the same spot on the platform as it turns around
+    for _ in 0..150 {
+        step(&mut world, &mut dispatcher, 1);
+        let physics_data = physics(&world, fighter);
+        assert_eq!(physics_data.ground, Some(0));
+        assert_eq!(physics_data.position - platform(&world, 0).position, offset);
+    }
+}
+
+#[test]
+fn lifted_by_rising_platform() {
+    let lift = shuttle(80, PointFi32::new(0, 0), PointFi32::new(0, -200), 50);
+    let (mut world, mut dispatcher, fighter) =
+        setup(vec![lift], TickRate::default(), PointFi32::new(0, 0));
+    for _ in 0..100 {
+        step(&mut world, &mut dispatcher, 1);
+        let physics_data = physics(&world, fighter);
+        assert_eq!(physics_data.ground, Some(0));
+        assert_eq!(physics_data.position, platform(&world, 0).position);
+    }
+}
+
+#[test]
+fn launched_off_keeps_momentum() {
+    let moving = shuttle(80, PointFi32::new(-100, 0), PointFi32::new(100, 0), 100);
+    let (mut world, mut dispatcher, fighter) =
+        setup(vec![moving], TickRate::default(), PointFi32::new(-100, 0));
+    step(&mut world, &mut dispatcher, 10);
+    assert_eq!(physics(&world, fighter).ground, Some(0));
+
+    world
+        .write_storage::<PhysicsData>()
+        .get_mut(fighter)
+        .unwrap()
+        .speed = PointFi32::new(0, -10);
+    step(&mut world, &mut dispatcher, 1);
+    let physics_data = physics(&world, fighter);
+    assert_eq!(physics_data.ground, None);
+    assert_eq!(physics_data.speed.x, platform(&world, 0).velocity.x);
+    assert_eq!(physics_data.speed.x, Fi32::from_num(2));
+}
+
+#[test]
+fn drops_off_the_end() {
+    let ledge = Platform::fixed(PointFi32::new(0, 0), Fi32::from_num(50));
+    let (mut world, mut dispatcher, fighter) =
+        setup(vec![ledge], TickRate::default(), PointFi32::new(45, 0));
+    step(&mut world, &mut dispatcher, 1);
+    assert_eq!(physics(&world, fighter).ground, Some(0));
+
+    world
+        .write_storage::<PhysicsData>()
+        .get_mut(fighter)
+        .unwrap()
+        .speed = PointFi32::new(10, 0);
+    step(&mut world, &mut dispatcher, 30);
+    let physics_data = physics(&world, fighter);
+    assert_eq!(physics_data.ground, None);
+    assert!(physics_data.position.y > Fi32::from_num(100));
+}
+
+/// Push the entity along each frame, as if running
+fn walk(world: &mut World, dispatcher: &mut Dispatcher, entity: Entity, speed: i32) {
+    world
+        .write_storage::<PhysicsData>()
+        .get_mut(entity)
+        .unwrap()
+        .speed
+        .x = Fi32::from_num(speed);
+    step(world, dispatcher, 1);
+}
+
+#[test]
+fn lands_on_slope() {
+    let slope = Platform::segment(PointFi32::new(0, 0), PointFi32::new(200, -100));
+    let (mut world, mut dispatcher, fighter) =
+        setup(vec![slope], TickRate::default(), PointFi32::new(100, -200));
+    step(&mut world, &mut dispatcher, 60);
+    let physics_data = physics(&world, fighter);
+    assert_eq!(physics_data.ground, Some(0));
+    assert_eq!(physics_data.position, PointFi32::new(100, -50));
+}
+
+#[test]
+fn sticks_to_downward_slopes() {
+    let floor = vec![
+        Platform::segment(PointFi32::new(-200, 0), PointFi32::new(0, 0)),
+        Platform::segment(PointFi32::new(0, 0), PointFi32::new(200, 100)),
+        Platform::segment(PointFi32::new(200, 100), PointFi32::new(400, 100)),
+    ];
+    let (mut world, mut dispatcher, fighter) =
+        setup(floor, TickRate::default(), PointFi32::new(-40, 0));
+    step(&mut world, &mut dispatcher, 1);
+    let mut previous = physics(&world, fighter).position;
+    while previous.x < Fi32::from_num(300) {
+        walk(&mut world, &mut dispatcher, fighter, 8);
+        let physics_data = physics(&world, fighter);
+        let ground = physics_data.ground.expect("launched off the slope");
+        assert_eq!(
+            physics_data.position.y,
+            platform(&world, ground).height_at(physics_data.position.x)
+        );
+        assert!(physics_data.position.x > previous.x);
+        previous = physics_data.position;
+    }
+    assert_eq!(physics(&world, fighter).ground, Some(2));
+    assert_eq!(previous.y, Fi32::from_num(100));
+}
+
+#[test]
+fn walks_up_slopes_slower() {
+    let floor = vec![
+        Platform::segment(PointFi32::new(-200, 0), PointFi32::new(0, 0)),
+        Platform::segment(PointFi32::new(0, 0), PointFi32::new(100, -100)),
+    ];
+    let (mut world, mut dispatcher, fighter) =
+        setup(floor, TickRate::default(), PointFi32::new(-50, 0));
+    step(&mut world, &mut dispatcher, 1);
+    let flat_start = physics(&world, fighter).position.x;
+    walk(&mut world, &mut dispatcher, fighter, 8);
+    let flat = physics(&world, fighter).position.x - flat_start;
+
+    while physics(&world, fighter).ground == Some(0) {
+        walk(&mut world, &mut dispatcher, fighter, 8);
+    }
+    let slope_start = physics(&world, fighter).position;
+    walk(&mut world, &mut dispatcher, fighter, 8);
+    let physics_data = physics(&world, fighter);
+    assert_eq!(physics_data.ground, Some(1));
+    assert!(physics_data.position.y < slope_start.y);
+    assert!(physics_data.position.x - slope_start.x < flat);
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use specs::{Dispatcher, Entity, World, WorldExt};
+
+use common::{hold, physics, state, step};
+use sm::characters::Character;
+use sm::{AttackKind, Input, PlayerState, PlayerStatus, PointFi32, Stick, TickRate};
+
+/// A fighter on a wide floor, driven by the input buffer
+fn setup(position: PointFi32) -> (World, Dispatcher<'static, 'static>, Entity) {
+    let (mut world, dispatcher, fighter) =
+        common::setup(common::floor(), TickRate::default(), position);
+    common::control(&mut world, fighter);
+    (world, dispatcher, fighter)
+}
+
+#[test]
+fn air_dodge_falls_after_its_travel() {
+    let (mut world, mut dispatcher, fighter) = setup(PointFi32::new(0, -400));
+    {
+        let mut states = world.write_storage::<PlayerState>();
+        let player_state = states.get_mut(fighter).unwrap();
+        player_state.status = PlayerStatus::AirDodge;
+        player_state.animation_counter = 0;
+        player_state.dodge_direction = PointFi32::new(1, 0);
+    }
+    let travel = Character::Fighter.dodges().air_dodge.travel.len() as u32;
+    step(&mut world, &mut dispatcher, travel + 1);
+    let start = physics(&world, fighter).position;
+    step(&mut world, &mut dispatcher, 10);
+    assert_eq!(state(&world, fighter).status, PlayerStatus::AirDodge);
+    let physics_data = physics(&world, fighter);
+    assert!(physics_data.speed.y.is_positive());
+    assert!(physics_data.position.y > start.y);
+}
+
+#[test]
+fn raising_shield_opens_parry_window() {
+    let (mut world, mut dispatcher, fighter) = setup(PointFi32::new(0, 0));
+    step(&mut world, &mut dispatcher, 5);
+    hold(&mut world, &mut dispatcher, &[Input::Shield]);
+    assert!(state(&world, fighter).is_parrying(&TickRate::default()));
+    for _ in 0..sm::PARRY_WINDOW {
+        hold(&mut world, &mut dispatcher, &[Input::Shield]);
+    }
+    assert_eq!(state(&world, fighter).status, PlayerStatus::Blocking);
+    assert!(!state(&world, fighter).is_parrying(&TickRate::default()));
+}
+
+#[test]
+fn blockstun_does_not_open_parry_window() {
+    let (mut world, mut dispatcher, fighter) = setup(PointFi32::new(0, 0));
+    step(&mut world, &mut dispatcher, 5);
+    for _ in 0..sm::PARRY_WINDOW * 2 {
+        hold(&mut world, &mut dispatcher, &[Input::Shield]);
+    }
+    // As Combat leaves a shielded hit with short shieldstun
+    {
+        let mut states = world.write_storage::<PlayerState>();
+        let player_state = states.get_mut(fighter).unwrap();
+        player_state.status = PlayerStatus::Blockstun;
+        player_state.stun = 4;
+        player_state.animation_counter = 0;
+    }
+    while state(&world, fighter).status == PlayerStatus::Blockstun {
+        hold(&mut world, &mut dispatcher, &[Input::Shield]);
+    }
+    assert_eq!(state(&world, fighter).status, PlayerStatus::Blocking);
+    assert!(!state(&world, fighter).is_parrying(&TickRate::default()));
+}
+
+#[test]
+fn up_just_before_attack_smashes() {
+    let (mut world, mut dispatcher, fighter) = setup(PointFi32::new(0, 0));
+    step(&mut world, &mut dispatcher, 5);
+    hold(&mut world, &mut dispatcher, &[Input::Up]);
+    assert_eq!(state(&world, fighter).status, PlayerStatus::Idle);
+    hold(&mut world, &mut dispatcher, &[Input::Up, Input::Attack]);
+    let player_state = state(&world, fighter);
+    assert_eq!(player_state.status, PlayerStatus::Attacking);
+    assert_eq!(player_state.attack, AttackKind::Smash(Stick::Up));
+}
+
+#[test]
+fn held_up_tilts() {
+    let (mut world, mut dispatcher, fighter) = setup(PointFi32::new(0, 0));
+    step(&mut world, &mut dispatcher, 5);
+    for _ in 0..=sm::SMASH_INPUT_WINDOW {
+        hold(&mut world, &mut dispatcher, &[Input::Up]);
+    }
+    hold(&mut world, &mut dispatcher, &[Input::Up, Input::Attack]);
+    let player_state = state(&world, fighter);
+    assert_eq!(player_state.status, PlayerStatus::Attacking);
+    assert_eq!(player_state.attack, AttackKind::Tilt(Stick::Up));
+}
+
+#[test]
+fn hitlag_freezes_shield_health() {
+    let (mut world, mut dispatcher, fighter) = setup(PointFi32::new(0, 0));
+    step(&mut world, &mut dispatcher, 5);
+    for _ in 0..sm::PARRY_WINDOW * 2 {
+        hold(&mut world, &mut dispatcher, &[Input::Shield]);
+    }
+    world
+        .write_storage::<sm::Hitlag>()
+        .insert(fighter, sm::Hitlag::new(10, false))
+        .unwrap();
+    let health = world
+        .read_storage::<sm::Shield>()
+        .get(fighter)
+        .unwrap()
+        .health;
+    for _ in 0..5 {
+        hold(&mut world, &mut dispatcher, &[Input::Shield]);
+    }
+    assert_eq!(state(&world, fighter).status, PlayerStatus::Blocking);
+    let shield = world
+        .read_storage::<sm::Shield>()
+        .get(fighter)
+        .unwrap()
+        .clone();
+    assert_eq!(shield.health, health);
+}
+
+#[test]
+fn attacks_without_a_jab() {
+    let (mut world, mut dispatcher, fighter) = setup(PointFi32::new(0, 0));
+    world
+        .write_storage::<sm::Moveset>()
+        .get_mut(fighter)
+        .unwrap()
+        .jab
+        .clear();
+    step(&mut world, &mut dispatcher, 5);
+    hold(&mut world, &mut dispatcher, &[Input::Attack]);
+    let player_state = state(&world, fighter);
+    assert_eq!(player_state.status, PlayerStatus::Attacking);
+    assert_eq!(player_state.attack, AttackKind::Tilt(Stick::Forward));
+
+    // A jab left over from before the move table changed ends without continuing
+    {
+        let mut states = world.write_storage::<PlayerState>();
+        let player_state = states.get_mut(fighter).unwrap();
+        player_state.attack = AttackKind::Jab(0);
+        player_state.animation_counter = 0;
+    }
+    hold(&mut world, &mut dispatcher, &[Input::Attack]);
+    let player_state = state(&world, fighter);
+    assert_eq!(player_state.status, PlayerStatus::Idle);
+    assert_eq!(player_state.attack, AttackKind::Jab(0));
+}
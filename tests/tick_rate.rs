@@ -0,0 +1,231 @@
+mod common;
+
+use specs::{Builder, WorldExt};
+
+use common::{control, floor, hold, physics, setup, state, step};
+use sm::characters::Character;
+use sm::{
+    AttackKind, Direction, Fi32, Input, PlayerState, PlayerStatus, PointFi32, Stage, Stick,
+    TickRate, Wall,
+};
+
+/// Ticks taken to land from a height
+fn fall(tick_rate: TickRate) -> u32 {
+    let (mut world, mut dispatcher, fighter) = setup(floor(), tick_rate, PointFi32::new(0, -200));
+    let mut ticks = 0;
+    while physics(&world, fighter).ground.is_none() {
+        step(&mut world, &mut dispatcher, 1);
+        ticks += 1;
+        assert!(ticks < 1000, "never landed");
+    }
+    ticks
+}
+
+/// How far a Samurai has moved `frames` frames at `BASE_TICK_RATE` after wall jumping
+fn wall_jump(tick_rate: TickRate, frames: u32) -> PointFi32 {
+    let (mut world, mut dispatcher, _) = setup(floor(), tick_rate, PointFi32::new(-400, 0));
+    world.write_resource::<Stage>().walls.push(Wall {
+        x: Fi32::from_num(100),
+        top: Fi32::from_num(-800),
+        bottom: Fi32::ZERO,
+        normal: Direction::Left,
+    });
+    let samurai = Character::Samurai
+        .build(
+            world.create_entity(),
+            PointFi32::new(60, -400),
+            Direction::Right,
+        )
+        .with(sm::Player1)
+        .build();
+    while physics(&world, samurai).wall_contact.is_none() {
+        hold(
+            &mut world,
+            &mut dispatcher,
+            &[Input::Move(Direction::Right)],
+        );
+        assert!(
+            physics(&world, samurai).ground.is_none(),
+            "never reached the wall"
+        );
+    }
+    // Only jumping players wall jump, rather than jumping again
+    world
+        .write_storage::<PlayerState>()
+        .get_mut(samurai)
+        .unwrap()
+        .status = PlayerStatus::Jumping;
+    let start = physics(&world, samurai).position;
+    hold(&mut world, &mut dispatcher, &[Input::Jump]);
+    for _ in 1..tick_rate.ticks(frames) {
+        hold(&mut world, &mut dispatcher, &[]);
+    }
+    physics(&world, samurai).position - start
+}
+
+/// Ticks of hitstun from a Samurai forward smash, and where the fighter it hits comes to rest
+fn launch(tick_rate: TickRate) -> (u32, PointFi32) {
+    let (mut world, mut dispatcher, fighter) = setup(floor(), tick_rate, PointFi32::new(40, 0));
+    Character::Samurai
+        .build(
+            world.create_entity(),
+            PointFi32::new(0, 0),
+            Direction::Right,
+        )
+        .with(sm::Player1)
+        .build();
+    for _ in 0..tick_rate.ticks(5) {
+        hold(&mut world, &mut dispatcher, &[]);
+    }
+    let smash = [Input::Move(Direction::Right), Input::Attack];
+    hold(&mut world, &mut dispatcher, &smash);
+    let mut hitstun = 0;
+    for _ in 0..tick_rate.ticks(120) {
+        hold(&mut world, &mut dispatcher, &[]);
+        let status = world
+            .read_storage::<PlayerState>()
+            .get(fighter)
+            .unwrap()
+            .status;
+        if status == PlayerStatus::Hitstun {
+            hitstun += 1;
+        }
+    }
+    (hitstun, physics(&world, fighter).position)
+}
+
+#[test]
+fn base_rate_keeps_tuned_stats() {
+    let stats = Character::Samurai
+        .movement_stats()
+        .per_tick(&TickRate::default());
+    assert_eq!(stats.max_speed, Fi32::from_num(17));
+    assert_eq!(stats.acceleration, Fi32::from_num(2.5));
+    assert_eq!(stats.friction, Fi32::from_num(1.2));
+    assert_eq!(stats.gravity, Fi32::from_num(1.8));
+    assert_eq!(stats.air_acceleration, Fi32::from_num(1));
+}
+
+#[test]
+fn rescales_to_tick_rate() {
+    let tick_rate = TickRate {
+        hz: 120,
+        ..TickRate::default()
+    };
+    assert_eq!(tick_rate.per_tick(Fi32::from_num(840)), Fi32::from_num(7));
+    assert_eq!(
+        tick_rate.per_tick_squared(Fi32::from_num(7200)),
+        Fi32::from_num(0.5)
+    );
+    assert_eq!(tick_rate.ticks(6), 12);
+    assert_eq!(TickRate::default().ticks(6), 6);
+    assert_eq!(tick_rate.frames(13), 6);
+    assert_eq!(tick_rate.frames(tick_rate.ticks(7)), 7);
+    assert_eq!(TickRate::default().frames(6), 6);
+    assert_eq!(tick_rate.per_frame(Fi32::from_num(9)), Fi32::from_num(4.5));
+    assert_eq!(
+        tick_rate.per_frame_squared(Fi32::from_num(2)),
+        Fi32::from_num(0.5)
+    );
+    assert_eq!(
+        tick_rate.frame_time(),
+        std::time::Duration::from_nanos(1_000_000_000 / 120)
+    );
+}
+
+#[test]
+fn slow_motion_lengthens_frames() {
+    let tick_rate = TickRate {
+        speed: Fi32::from_num(0.5),
+        ..TickRate::default()
+    };
+    assert_eq!(
+        tick_rate.frame_time(),
+        std::time::Duration::from_nanos(1_000_000_000 / 30)
+    );
+}
+
+#[test]
+fn falls_as_fast_at_any_rate() {
+    let base = fall(TickRate::default());
+    let double = fall(TickRate {
+        hz: 120,
+        ..TickRate::default()
+    });
+    assert!(
+        double.abs_diff(base * 2) <= 2,
+        "{base} at 60 Hz, {double} at 120 Hz"
+    );
+}
+
+#[test]
+fn lands_with_substeps() {
+    for substeps in [1, 2, 4] {
+        let (mut world, mut dispatcher, fighter) = setup(
+            floor(),
+            TickRate {
+                substeps,
+                ..TickRate::default()
+            },
+            PointFi32::new(0, -100),
+        );
+        step(&mut world, &mut dispatcher, 60);
+        let physics_data = physics(&world, fighter);
+        assert_eq!(physics_data.ground, Some(0));
+        assert_eq!(physics_data.position, PointFi32::new(0, 0));
+        assert_eq!(physics_data.speed, PointFi32::new(0, 0));
+    }
+}
+
+#[test]
+fn wall_jumps_as_far_at_any_rate() {
+    let base = wall_jump(TickRate::default(), 20);
+    let double = wall_jump(
+        TickRate {
+            hz: 120,
+            ..TickRate::default()
+        },
+        20,
+    );
+    assert_eq!(base.x, double.x);
+    assert!(
+        (base.y - double.y).abs() <= 8,
+        "{base:?} at 60 Hz, {double:?} at 120 Hz"
+    );
+}
+
+#[test]
+fn launches_as_far_at_any_rate() {
+    let (base_hitstun, base_rest) = launch(TickRate::default());
+    let (double_hitstun, double_rest) = launch(TickRate {
+        hz: 120,
+        ..TickRate::default()
+    });
+    assert!(base_hitstun > 0);
+    assert_eq!(double_hitstun, base_hitstun * 2);
+    assert!(
+        (base_rest.x - double_rest.x).abs() <= 4,
+        "{base_rest:?} at 60 Hz, {double_rest:?} at 120 Hz"
+    );
+}
+
+#[test]
+fn smash_window_lasts_as_long_at_any_rate() {
+    let tick_rate = TickRate {
+        hz: sm::MAX_TICK_RATE,
+        ..TickRate::default()
+    };
+    let (mut world, mut dispatcher, fighter) = setup(floor(), tick_rate, PointFi32::new(0, 0));
+    control(&mut world, fighter);
+    for _ in 0..tick_rate.ticks(5) {
+        hold(&mut world, &mut dispatcher, &[]);
+    }
+    // Up pressed more ticks before attacking than the window is long at the base rate
+    for _ in 0..tick_rate.ticks(sm::SMASH_INPUT_WINDOW as u32 - 1) {
+        hold(&mut world, &mut dispatcher, &[Input::Up]);
+    }
+    hold(&mut world, &mut dispatcher, &[Input::Up, Input::Attack]);
+    let player_state = state(&world, fighter);
+    assert_eq!(player_state.status, PlayerStatus::Attacking);
+    assert_eq!(player_state.attack, AttackKind::Smash(Stick::Up));
+}